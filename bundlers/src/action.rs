@@ -1,35 +1,226 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::contract_utils::handler_result::HandlerResult;
-use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+use bundlr_contracts_shared::{u128_utils, Address, Amount, BlockHeight};
 use serde::{Deserialize, Serialize};
 
-use crate::{error::ContractError, state::State};
+use crate::{
+    actions::validators::SlashProposal,
+    error::ContractError,
+    state::{
+        Bundler, Delegation, Destination, HeartbeatStats, JailRecord, Liveness, PendingConfig,
+        Profile, Role, StakeToken, State, Tier, WithdrawDelay,
+    },
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "function")]
 pub enum Action {
     Bundlers,
+    #[serde(rename_all = "camelCase")]
+    ActiveBundlers {
+        start_after: Option<Address>,
+        limit: Option<usize>,
+    },
+    #[serde(rename_all = "camelCase")]
+    LeavingBundlers {
+        start_after: Option<Address>,
+        limit: Option<usize>,
+    },
+    BundlerStatus {
+        address: Address,
+    },
+    Bundler {
+        address: Address,
+    },
+    BundlersByCurrency {
+        currency: String,
+    },
     WithdrawDelay,
     Stake,
     Token,
-    Join,
+    Treasury,
+    ValidatorsContract,
+    PendingConfig,
+    Join {
+        stake: Option<Amount>,
+        profile: Option<Profile>,
+        tier: Option<String>,
+        token: Option<Address>,
+    },
+    UpdateProfile {
+        profile: Profile,
+    },
     Leave,
+    CancelLeave,
+    IncreaseStake {
+        amount: Amount,
+    },
+    DecreaseStake {
+        amount: Amount,
+    },
+    #[serde(rename_all = "camelCase")]
+    WithdrawUnbonded {
+        maturity_height: BlockHeight,
+    },
     SyncSlashed,
+    Delegations {
+        bundler: Address,
+    },
+    Delegate {
+        bundler: Address,
+        amount: Amount,
+    },
+    Undelegate {
+        bundler: Address,
+        amount: Amount,
+    },
+    WithdrawDelegation {
+        bundler: Address,
+    },
+    PendingRewards {
+        address: Address,
+    },
+    DepositRewards {
+        amount: Amount,
+    },
+    ClaimRewards,
+    JailHistory {
+        address: Address,
+    },
+    Jail {
+        bundler: Address,
+        reason: String,
+    },
+    Unjail,
+    Tiers,
+    SetTier {
+        name: String,
+        capacity: u64,
+        stake: Amount,
+    },
+    RemoveTier {
+        name: String,
+    },
+    StakeTokens,
+    SetStakeToken {
+        token: Address,
+        weight: u32,
+    },
+    RemoveStakeToken {
+        token: Address,
+    },
+    ChangeTier {
+        tier: Option<String>,
+    },
+    SlashProposals {
+        bundler: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetValidatorsContract {
+        validators_contract: Address,
+    },
+    ForceRemove {
+        bundler: Address,
+        destination: Destination,
+        reason: String,
+    },
+    Liveness {
+        address: Address,
+    },
+    Heartbeat {
+        epoch: u64,
+        stats: HeartbeatStats,
+    },
     Withdraw,
     AllowedInteractors,
-    AddAllowedInteractor { interactor: Address },
-    RemoveAllowedInteractor { interactor: Address },
+    Roles,
+    GrantRole {
+        role: Role,
+        address: Address,
+    },
+    RevokeRole {
+        role: Role,
+        address: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateConfig {
+        withdraw_delay: Option<WithdrawDelay>,
+        stake: Option<Amount>,
+        treasury: Option<Address>,
+        heartbeat_window: Option<u64>,
+        max_missed_heartbeats: Option<u64>,
+        config_timelock: Option<u64>,
+    },
+    AddAllowedInteractor {
+        interactor: Address,
+    },
+    RemoveAllowedInteractor {
+        interactor: Address,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeavingBundler {
+    pub address: Address,
+    pub withdraw_height: BlockHeight,
+    // blocks remaining until the bundler can withdraw, zero if it already can
+    #[serde(with = "u128_utils")]
+    pub blocks_remaining: u128,
+    // timestamp after which the bundler can withdraw, estimated when the
+    // withdraw delay is in blocks
+    pub unlock_time: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundlerStatus {
+    Active,
+    #[serde(rename_all = "camelCase")]
+    Leaving {
+        height: BlockHeight,
+        unlock_time: u64,
+    },
+    Jailed(BlockHeight),
+    // active bundler which has missed too many heartbeat windows
+    Inactive,
+    Withdrawable,
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum QueryResponseMsg {
     Token(Address),
+    Treasury(Option<Address>),
+    ValidatorsContract(Option<Address>),
+    PendingConfig(Option<PendingConfig>),
     Stake(Amount),
     Bundlers(HashMap<Address, Option<BlockHeight>>),
-    WithdrawDelay(u16),
+    Bundler(Box<Bundler>),
+    ActiveBundlers(Vec<Address>),
+    LeavingBundlers(Vec<LeavingBundler>),
+    BundlerStatus(BundlerStatus),
+    BundlersByCurrency(HashMap<Address, Profile>),
+    WithdrawDelay(WithdrawDelay),
     AllowedInteractors(HashSet<Address>),
+    Roles(HashMap<Address, HashSet<Role>>),
+    Delegations(HashMap<Address, Delegation>),
+    PendingRewards(Amount),
+    JailHistory(Vec<JailRecord>),
+    Tiers(HashMap<String, Tier>),
+    StakeTokens(HashMap<Address, StakeToken>),
+    SlashProposals {
+        open: Vec<SlashProposal>,
+        concluded: Vec<SlashProposal>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Liveness {
+        liveness: Liveness,
+        missed_windows: u64,
+        inactive: bool,
+    },
 }
 
 pub type ActionResult = Result<HandlerResult<State, QueryResponseMsg>, ContractError>;
@@ -0,0 +1,293 @@
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{PendingConfig, Role, State, WithdrawDelay},
+};
+
+use super::roles::has_role;
+
+// Schedules a configuration change which takes effect once the config timelock
+// has passed, a new change replaces the pending one
+#[allow(clippy::too_many_arguments)]
+pub async fn update_config(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    current_block_height: u128,
+    withdraw_delay: Option<WithdrawDelay>,
+    stake: Option<Amount>,
+    treasury: Option<Address>,
+    heartbeat_window: Option<u64>,
+    max_missed_heartbeats: Option<u64>,
+    config_timelock: Option<u64>,
+) -> ActionResult {
+    if caller != owner && !has_role(&state, &caller, Role::Admin) {
+        return Err(ContractError::Forbidden);
+    }
+
+    if withdraw_delay.is_none()
+        && stake.is_none()
+        && treasury.is_none()
+        && heartbeat_window.is_none()
+        && max_missed_heartbeats.is_none()
+        && config_timelock.is_none()
+    {
+        return Err(ContractError::NothingToUpdate);
+    }
+
+    if withdraw_delay == Some(WithdrawDelay::Blocks(0))
+        || withdraw_delay == Some(WithdrawDelay::Duration { seconds: 0 })
+    {
+        return Err(ContractError::InvalidWithdrawDelay);
+    }
+
+    if stake == Some(Amount::ZERO) {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    let effective_height = BlockHeight(current_block_height + state.config_timelock as u128);
+
+    state.pending_config = Some(PendingConfig {
+        withdraw_delay,
+        stake,
+        treasury,
+        heartbeat_window,
+        max_missed_heartbeats,
+        config_timelock,
+        effective_height,
+    });
+
+    Ok(HandlerResult::NewState(state))
+}
+
+// Applies the pending configuration change if it has become effective, it does
+// not affect stakes already deposited or withdraw heights already computed
+pub fn apply_pending_config(mut state: State, current_block_height: u128) -> State {
+    let pending = match state.pending_config.take() {
+        Some(pending) if *pending.effective_height <= current_block_height => pending,
+        pending => {
+            state.pending_config = pending;
+            return state;
+        }
+    };
+
+    if let Some(withdraw_delay) = pending.withdraw_delay {
+        state.withdraw_delay = withdraw_delay;
+    }
+    if let Some(stake) = pending.stake {
+        state.stake = stake;
+    }
+    if pending.treasury.is_some() {
+        state.treasury = pending.treasury;
+    }
+    if let Some(heartbeat_window) = pending.heartbeat_window {
+        state.heartbeat_window = heartbeat_window;
+    }
+    if let Some(max_missed_heartbeats) = pending.max_missed_heartbeats {
+        state.max_missed_heartbeats = max_missed_heartbeats;
+    }
+    if let Some(config_timelock) = pending.config_timelock {
+        state.config_timelock = config_timelock;
+    }
+
+    state
+}
+
+pub fn pending_config(state: State) -> ActionResult {
+    Ok(HandlerResult::QueryResponse(
+        QueryResponseMsg::PendingConfig(state.pending_config),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        action::ActionResult,
+        error::ContractError,
+        state::{Role, State, WithdrawDelay},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{apply_pending_config, update_config};
+
+    fn update_stake(state: State, caller: Address, height: u128, stake: u128) -> ActionResult {
+        let mut pool = LocalPool::new();
+
+        pool.run_until(update_config(
+            state,
+            address("owner"),
+            caller,
+            height,
+            None,
+            Some(Amount::from(stake)),
+            None,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    #[test]
+    fn change_is_applied_after_the_config_timelock() {
+        let state = new_state(update_stake(state(), address("owner"), 100, 200));
+
+        assert_eq!(
+            state.pending_config.as_ref().unwrap().effective_height,
+            BlockHeight(150)
+        );
+
+        let state = apply_pending_config(state, 149);
+        assert_eq!(state.stake, Amount::from(100));
+        assert!(state.pending_config.is_some());
+
+        let state = apply_pending_config(state, 150);
+        assert_eq!(state.stake, Amount::from(200));
+        assert!(state.pending_config.is_none());
+    }
+
+    #[test]
+    fn config_timelock_does_not_follow_the_withdraw_delay() {
+        let mut state = state();
+        state.withdraw_delay = WithdrawDelay::Duration { seconds: 12_000 };
+
+        let state = new_state(update_stake(state, address("owner"), 100, 200));
+
+        assert_eq!(
+            state.pending_config.unwrap().effective_height,
+            BlockHeight(150)
+        );
+    }
+
+    #[test]
+    fn config_timelock_change_waits_for_the_current_timelock() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(update_config(
+            state(),
+            address("owner"),
+            address("owner"),
+            100,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(1000),
+        )));
+
+        let state = apply_pending_config(state, 150);
+        assert_eq!(state.config_timelock, 1000);
+
+        let state = new_state(update_stake(state, address("owner"), 200, 200));
+        assert_eq!(
+            state.pending_config.unwrap().effective_height,
+            BlockHeight(1200)
+        );
+    }
+
+    #[test]
+    fn new_change_replaces_the_pending_one() {
+        let state = new_state(update_stake(state(), address("owner"), 100, 200));
+        let state = new_state(update_stake(state, address("owner"), 120, 300));
+
+        let pending = state.pending_config.unwrap();
+        assert_eq!(pending.stake, Some(Amount::from(300)));
+        assert_eq!(pending.effective_height, BlockHeight(170));
+    }
+
+    #[test]
+    fn only_owner_and_admins_can_update() {
+        let mut state = state();
+        state
+            .roles
+            .insert(address("admin"), HashSet::from([Role::Admin]));
+        state
+            .roles
+            .insert(address("manager"), HashSet::from([Role::AllowlistManager]));
+
+        assert!(update_stake(state.clone(), address("admin"), 100, 200).is_ok());
+        assert_eq!(
+            update_stake(state, address("manager"), 100, 200).err(),
+            Some(ContractError::Forbidden)
+        );
+    }
+
+    #[test]
+    fn invalid_changes_are_rejected() {
+        let mut pool = LocalPool::new();
+
+        assert_eq!(
+            update_stake(state(), address("owner"), 100, 0).err(),
+            Some(ContractError::AmountMustBeHigherThanZero)
+        );
+
+        let result = pool.run_until(update_config(
+            state(),
+            address("owner"),
+            address("owner"),
+            100,
+            Some(WithdrawDelay::Duration { seconds: 0 }),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(result.err(), Some(ContractError::InvalidWithdrawDelay));
+
+        let result = pool.run_until(update_config(
+            state(),
+            address("owner"),
+            address("owner"),
+            100,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(result.err(), Some(ContractError::NothingToUpdate));
+    }
+
+    #[test]
+    fn higher_stake_does_not_affect_staked_and_leaving_bundlers() {
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+        let mut leaving = bundler(100, 1);
+        leaving.leaving = Some(BlockHeight(110));
+        state.bundlers.insert(address("b2"), leaving);
+
+        let mut pool = LocalPool::new();
+        let state = new_state(pool.run_until(update_config(
+            state,
+            address("owner"),
+            address("owner"),
+            100,
+            Some(WithdrawDelay::Blocks(100)),
+            Some(Amount::from(200)),
+            None,
+            None,
+            None,
+            None,
+        )));
+        let state = apply_pending_config(state, 150);
+
+        let staked = &state.bundlers[&address("b1")];
+        assert_eq!(staked.stake, Amount::from(100));
+        assert!(staked.is_active());
+        assert_eq!(state.active_stake, Amount::from(100));
+
+        let leaving = &state.bundlers[&address("b2")];
+        assert_eq!(leaving.leaving, Some(BlockHeight(110)));
+        assert!(leaving.can_withdraw(150, 0));
+    }
+}
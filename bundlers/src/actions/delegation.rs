@@ -0,0 +1,460 @@
+use bundlr_contracts_shared::{Address, Amount};
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{is_matured, Delegation, State},
+};
+
+use super::{
+    rewards::{settle_bundler, settle_delegation},
+    token,
+};
+
+pub async fn delegate(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    bundler: Address,
+    amount: Amount,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    match state.bundlers.get(&bundler) {
+        Some(entry) => {
+            if let Some(block) = entry.leaving {
+                return Err(ContractError::AlreadyLeaving(bundler, block));
+            }
+            if let Some(block) = entry.jailed_until {
+                return Err(ContractError::BundlerJailed(block));
+            }
+        }
+        None => {
+            return Err(ContractError::InvalidBundler(bundler));
+        }
+    }
+
+    token::transfer_to_contract(&state.token, caller.clone(), amount).await?;
+
+    settle_bundler(&mut state, &bundler, current_block_height);
+    settle_delegation(&mut state, &bundler, &caller);
+
+    // We can safely unwrap here, the bundler was checked above
+    let entry = state.bundlers.get_mut(&bundler).unwrap();
+    entry.delegated += amount;
+    let delegator_reward_index = entry.delegator_reward_index;
+    state.active_stake += amount;
+
+    state
+        .delegations
+        .entry(bundler)
+        .or_default()
+        .entry(caller)
+        .or_insert_with(|| Delegation {
+            reward_index: delegator_reward_index,
+            ..Default::default()
+        })
+        .amount += amount;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+// Starts unbonding delegated tokens, undelegating more restarts the withdraw
+// delay for the whole unbonding amount
+pub async fn undelegate(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+    bundler: Address,
+    amount: Amount,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    settle_bundler(&mut state, &bundler, current_block_height);
+    settle_delegation(&mut state, &bundler, &caller);
+
+    let (withdrawable_at, withdrawable_until) =
+        state.withdraw_maturity(current_block_height, current_timestamp);
+    let delegation = state
+        .delegations
+        .get_mut(&bundler)
+        .and_then(|delegations| delegations.get_mut(&caller))
+        .ok_or_else(|| ContractError::InvalidDelegation(bundler.clone()))?;
+
+    if delegation.amount < amount {
+        return Err(ContractError::InsufficientDelegation(delegation.amount));
+    }
+
+    delegation.amount -= amount;
+    delegation.unbonding += amount;
+    delegation.withdrawable_at = Some(withdrawable_at);
+    delegation.withdrawable_until = withdrawable_until;
+
+    if let Some(entry) = state.bundlers.get_mut(&bundler) {
+        entry.delegated -= amount;
+        if entry.is_active() {
+            state.active_stake -= amount;
+        }
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn withdraw_delegation(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+    bundler: Address,
+) -> ActionResult {
+    let delegations = state
+        .delegations
+        .get_mut(&bundler)
+        .ok_or_else(|| ContractError::InvalidDelegation(bundler.clone()))?;
+    let delegation = delegations
+        .get_mut(&caller)
+        .ok_or_else(|| ContractError::InvalidDelegation(bundler.clone()))?;
+
+    let matured = delegation.withdrawable_at.is_some_and(|withdrawable_at| {
+        is_matured(
+            withdrawable_at,
+            delegation.withdrawable_until,
+            current_block_height,
+            current_timestamp,
+        )
+    });
+    if !matured {
+        return Err(ContractError::WithdrawBlocked);
+    }
+
+    let amount = delegation.unbonding;
+    delegation.unbonding = Amount::ZERO;
+    delegation.withdrawable_at = None;
+    delegation.withdrawable_until = None;
+
+    if delegation.amount == Amount::ZERO {
+        delegations.remove(&caller);
+        if delegations.is_empty() {
+            state.delegations.remove(&bundler);
+        }
+    }
+
+    token::transfer(&state.token, caller, amount).await?;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn delegations(mut state: State, bundler: Address) -> ActionResult {
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::Delegations(
+        state.delegations.remove(&bundler).unwrap_or_default(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        actions::token::mock::{contract_address, take_transfers},
+        error::ContractError,
+        state::{State, WithdrawDelay},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{delegate, undelegate, withdraw_delegation};
+
+    // Bundler b1 with 100 staked and 300 delegated by d1
+    fn delegated_state() -> State {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+
+        let state = new_state(pool.run_until(delegate(
+            state,
+            address("d1"),
+            100,
+            address("b1"),
+            Amount::from(300),
+        )));
+        take_transfers();
+        state
+    }
+
+    #[test]
+    fn delegation_backs_the_bundler() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(delegate(
+            delegated_state(),
+            address("d1"),
+            100,
+            address("b1"),
+            Amount::from(200),
+        )));
+
+        assert_eq!(state.bundlers[&address("b1")].delegated, Amount::from(500));
+        assert_eq!(
+            state.delegations[&address("b1")][&address("d1")].amount,
+            Amount::from(500)
+        );
+        assert_eq!(state.active_stake, Amount::from(600));
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                address("d1"),
+                contract_address(),
+                Amount::from(200)
+            )]
+        );
+    }
+
+    #[test]
+    fn only_active_bundlers_accept_delegations() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        let mut leaving = bundler(100, 1);
+        leaving.leaving = Some(BlockHeight(110));
+        state.bundlers.insert(address("b1"), leaving);
+        let mut jailed = bundler(100, 1);
+        jailed.jailed_until = Some(BlockHeight(800));
+        state.bundlers.insert(address("b2"), jailed);
+
+        let result = pool.run_until(delegate(
+            state.clone(),
+            address("d1"),
+            100,
+            address("b1"),
+            Amount::from(100),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyLeaving(
+                address("b1"),
+                BlockHeight(110)
+            ))
+        );
+
+        let result = pool.run_until(delegate(
+            state.clone(),
+            address("d1"),
+            100,
+            address("b2"),
+            Amount::from(100),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::BundlerJailed(BlockHeight(800)))
+        );
+
+        let result = pool.run_until(delegate(
+            state,
+            address("d1"),
+            100,
+            address("b3"),
+            Amount::from(100),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidBundler(address("b3")))
+        );
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn undelegated_amount_unbonds_for_the_withdraw_delay() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(undelegate(
+            delegated_state(),
+            address("d1"),
+            100,
+            10_000,
+            address("b1"),
+            Amount::from(200),
+        )));
+
+        let delegation = &state.delegations[&address("b1")][&address("d1")];
+        assert_eq!(delegation.amount, Amount::from(100));
+        assert_eq!(delegation.unbonding, Amount::from(200));
+        assert_eq!(delegation.withdrawable_at, Some(BlockHeight(110)));
+        assert_eq!(state.bundlers[&address("b1")].delegated, Amount::from(100));
+        assert_eq!(state.active_stake, Amount::from(200));
+
+        let result = pool.run_until(undelegate(
+            state,
+            address("d1"),
+            100,
+            10_000,
+            address("b1"),
+            Amount::from(101),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientDelegation(Amount::from(100)))
+        );
+    }
+
+    #[test]
+    fn undelegating_again_restarts_the_delay() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(undelegate(
+            delegated_state(),
+            address("d1"),
+            100,
+            10_000,
+            address("b1"),
+            Amount::from(100),
+        )));
+        let state = new_state(pool.run_until(undelegate(
+            state,
+            address("d1"),
+            105,
+            10_600,
+            address("b1"),
+            Amount::from(100),
+        )));
+
+        let delegation = &state.delegations[&address("b1")][&address("d1")];
+        assert_eq!(delegation.unbonding, Amount::from(200));
+        assert_eq!(delegation.withdrawable_at, Some(BlockHeight(115)));
+
+        let result = pool.run_until(withdraw_delegation(
+            state,
+            address("d1"),
+            110,
+            11_200,
+            address("b1"),
+        ));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked));
+    }
+
+    #[test]
+    fn unbonded_delegation_is_withdrawn_after_the_delay() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(undelegate(
+            delegated_state(),
+            address("d1"),
+            100,
+            10_000,
+            address("b1"),
+            Amount::from(200),
+        )));
+
+        let result = pool.run_until(withdraw_delegation(
+            state.clone(),
+            address("d1"),
+            109,
+            11_080,
+            address("b1"),
+        ));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked));
+
+        let state = new_state(pool.run_until(withdraw_delegation(
+            state,
+            address("d1"),
+            110,
+            11_200,
+            address("b1"),
+        )));
+
+        let delegation = &state.delegations[&address("b1")][&address("d1")];
+        assert_eq!(delegation.amount, Amount::from(100));
+        assert_eq!(delegation.unbonding, Amount::ZERO);
+        assert_eq!(delegation.withdrawable_at, None);
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                contract_address(),
+                address("d1"),
+                Amount::from(200)
+            )]
+        );
+
+        // Nothing is left to withdraw
+        let result = pool.run_until(withdraw_delegation(
+            state,
+            address("d1"),
+            120,
+            12_400,
+            address("b1"),
+        ));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked));
+    }
+
+    #[test]
+    fn fully_withdrawn_delegation_is_removed() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(undelegate(
+            delegated_state(),
+            address("d1"),
+            100,
+            10_000,
+            address("b1"),
+            Amount::from(300),
+        )));
+        let state = new_state(pool.run_until(withdraw_delegation(
+            state,
+            address("d1"),
+            110,
+            11_200,
+            address("b1"),
+        )));
+
+        assert!(!state.delegations.contains_key(&address("b1")));
+        assert_eq!(state.active_stake, Amount::from(100));
+    }
+
+    #[test]
+    fn delegation_undelegated_with_duration_delay_matures_at_the_timestamp() {
+        let mut pool = LocalPool::new();
+
+        let mut state = delegated_state();
+        state.withdraw_delay = WithdrawDelay::Duration { seconds: 1200 };
+
+        let state = new_state(pool.run_until(undelegate(
+            state,
+            address("d1"),
+            100,
+            10_000,
+            address("b1"),
+            Amount::from(200),
+        )));
+        let delegation = &state.delegations[&address("b1")][&address("d1")];
+        assert_eq!(delegation.withdrawable_at, Some(BlockHeight(110)));
+        assert_eq!(delegation.withdrawable_until, Some(11_200));
+
+        let result = pool.run_until(withdraw_delegation(
+            state.clone(),
+            address("d1"),
+            115,
+            11_199,
+            address("b1"),
+        ));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked));
+
+        let state = new_state(pool.run_until(withdraw_delegation(
+            state,
+            address("d1"),
+            105,
+            11_200,
+            address("b1"),
+        )));
+        let delegation = &state.delegations[&address("b1")][&address("d1")];
+        assert_eq!(delegation.withdrawable_until, None);
+        assert_eq!(take_transfers().len(), 1);
+    }
+}
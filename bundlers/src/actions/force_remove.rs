@@ -0,0 +1,218 @@
+use bundlr_contracts_shared::{Address, BlockHeight};
+
+use crate::{
+    action::ActionResult,
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Destination, RemovalRecord, State},
+};
+
+use super::{rewards::settle_all, token};
+
+// Removes the bundler without it having to leave, its delegations are left in
+// place so that delegators can undelegate as usual
+#[allow(clippy::too_many_arguments)]
+pub async fn force_remove(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    current_block_height: u128,
+    bundler: Address,
+    destination: Destination,
+    reason: String,
+) -> ActionResult {
+    if caller != owner {
+        return Err(ContractError::Forbidden);
+    }
+
+    if reason.trim().is_empty() {
+        return Err(ContractError::MissingReason);
+    }
+
+    let to = match destination {
+        Destination::Bundler => bundler.clone(),
+        Destination::Treasury => state
+            .treasury
+            .clone()
+            .ok_or(ContractError::TreasuryNotSet)?,
+    };
+
+    let removed = match state.bundlers.get(&bundler) {
+        Some(removed) => removed,
+        None => {
+            return Err(ContractError::InvalidBundler(bundler));
+        }
+    };
+
+    let stake = removed
+        .unbonding
+        .values()
+        .fold(removed.stake, |total, amount| total + *amount);
+
+    settle_all(&mut state, &bundler, current_block_height);
+    // We can safely unwrap here, the bundler was checked above
+    let removed = state.bundlers.remove(&bundler).unwrap();
+    if removed.is_active() {
+        state.active_stake -= removed.weighted_stake() + removed.delegated;
+    }
+
+    token::transfer(removed.token(&state.token), to.clone(), stake).await?;
+
+    state.removals.push(RemovalRecord {
+        bundler,
+        destination,
+        recipient: to,
+        stake,
+        reason,
+        removed_at: BlockHeight(current_block_height),
+    });
+
+    Ok(HandlerResult::NewState(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        actions::token::mock,
+        error::ContractError,
+        state::{Destination, State},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::force_remove;
+
+    fn removable() -> State {
+        let mut state = state();
+        let mut b1 = bundler(100, 1);
+        b1.unbonding.insert(BlockHeight(200), Amount::from(20));
+        join(&mut state, "b1", b1);
+        state
+    }
+
+    #[test]
+    fn only_owner_can_force_remove() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(force_remove(
+            removable(),
+            address("owner"),
+            address("b1"),
+            100,
+            address("b1"),
+            Destination::Bundler,
+            "lost keys".to_string(),
+        ));
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+
+        let result = pool.run_until(force_remove(
+            removable(),
+            address("owner"),
+            address("owner"),
+            100,
+            address("b1"),
+            Destination::Bundler,
+            " ".to_string(),
+        ));
+        assert_eq!(result.err(), Some(ContractError::MissingReason));
+        assert!(mock::take_transfers().is_empty());
+    }
+
+    #[test]
+    fn stake_is_returned_to_bundler() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(force_remove(
+            removable(),
+            address("owner"),
+            address("owner"),
+            100,
+            address("b1"),
+            Destination::Bundler,
+            "lost keys".to_string(),
+        )));
+
+        assert!(state.bundlers.is_empty());
+        assert_eq!(state.active_stake, Amount::ZERO);
+        assert_eq!(
+            mock::take_transfers(),
+            vec![(
+                address("token"),
+                mock::contract_address(),
+                address("b1"),
+                Amount::from(120)
+            )]
+        );
+
+        let removal = &state.removals[0];
+        assert_eq!(removal.bundler, address("b1"));
+        assert_eq!(removal.destination, Destination::Bundler);
+        assert_eq!(removal.recipient, address("b1"));
+        assert_eq!(removal.stake, Amount::from(120));
+        assert_eq!(removal.reason, "lost keys");
+        assert_eq!(removal.removed_at, BlockHeight(100));
+    }
+
+    #[test]
+    fn stake_is_sent_to_treasury() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(force_remove(
+            removable(),
+            address("owner"),
+            address("owner"),
+            100,
+            address("b1"),
+            Destination::Treasury,
+            "rogue".to_string(),
+        ));
+        assert_eq!(result.err(), Some(ContractError::TreasuryNotSet));
+
+        let mut state = removable();
+        state.treasury = Some(address("treasury"));
+        let state = new_state(pool.run_until(force_remove(
+            state,
+            address("owner"),
+            address("owner"),
+            100,
+            address("b1"),
+            Destination::Treasury,
+            "rogue".to_string(),
+        )));
+
+        assert_eq!(
+            mock::take_transfers(),
+            vec![(
+                address("token"),
+                mock::contract_address(),
+                address("treasury"),
+                Amount::from(120)
+            )]
+        );
+        let removal = &state.removals[0];
+        assert_eq!(removal.destination, Destination::Treasury);
+        assert_eq!(removal.recipient, address("treasury"));
+        assert_eq!(removal.reason, "rogue");
+    }
+
+    #[test]
+    fn unknown_bundler_cannot_be_removed() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(force_remove(
+            removable(),
+            address("owner"),
+            address("owner"),
+            100,
+            address("b2"),
+            Destination::Bundler,
+            "rogue".to_string(),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidBundler(address("b2")))
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use bundlr_contracts_shared::{Address, BlockHeight};
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{HeartbeatStats, State},
+};
+
+use super::rewards::settle_bundler;
+
+pub async fn heartbeat(
+    mut state: State,
+    caller: Address,
+    current_height: u128,
+    epoch: u64,
+    stats: HeartbeatStats,
+) -> ActionResult {
+    // Rewards are settled before the heartbeat makes the bundler active again,
+    // the ones from the period it was inactive are forfeited
+    settle_bundler(&mut state, &caller, current_height);
+
+    let bundler = match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler,
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    };
+
+    let liveness = &mut bundler.liveness;
+
+    // Epochs are numbered by the bundler, each heartbeat has to report a new one
+    // so that the same stats cannot be counted twice.
+    if let Some(last_epoch) = liveness.last_epoch {
+        if epoch <= last_epoch {
+            return Err(ContractError::InvalidEpoch(last_epoch));
+        }
+    }
+
+    liveness.last_epoch = Some(epoch);
+    liveness.last_seen = Some(BlockHeight(current_height));
+    liveness.heartbeats += 1;
+    liveness.uploaded_bytes += stats.uploaded_bytes as u128;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn liveness(mut state: State, current_height: u128, address: Address) -> ActionResult {
+    let bundler = state
+        .bundlers
+        .remove(&address)
+        .ok_or(ContractError::InvalidBundler(address))?;
+
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::Liveness {
+        inactive: state.is_inactive(&bundler, current_height),
+        missed_windows: bundler.missed_heartbeat_windows(current_height, state.heartbeat_window),
+        liveness: bundler.liveness,
+    }))
+}
@@ -1,16 +1,27 @@
-use bundlr_contracts_shared::{contract_utils::js_imports::SmartWeave, Address};
+use bundlr_contracts_shared::Address;
 
 use crate::{
-    action::ActionResult, contract_utils::handler_result::HandlerResult, error::ContractError,
-    state::State,
+    action::ActionResult,
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Role, State},
 };
 
-pub async fn add(mut state: State, owner: Address, interactor: Address) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+use super::roles::has_role;
 
-    if caller != owner && !state.allowed_interactors.contains(&caller) {
+fn can_manage_interactors(state: &State, owner: &Address, caller: &Address) -> bool {
+    caller == owner
+        || has_role(state, caller, Role::Admin)
+        || has_role(state, caller, Role::AllowlistManager)
+}
+
+pub async fn add(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    interactor: Address,
+) -> ActionResult {
+    if !can_manage_interactors(&state, &owner, &caller) {
         return Err(ContractError::Forbidden);
     }
 
@@ -23,12 +34,13 @@ pub async fn add(mut state: State, owner: Address, interactor: Address) -> Actio
     Ok(HandlerResult::NewState(state))
 }
 
-pub async fn remove(mut state: State, owner: Address, interactor: Address) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
-
-    if caller != owner && !state.allowed_interactors.contains(&caller) {
+pub async fn remove(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    interactor: Address,
+) -> ActionResult {
+    if !can_manage_interactors(&state, &owner, &caller) {
         return Err(ContractError::Forbidden);
     }
 
@@ -38,3 +50,105 @@ pub async fn remove(mut state: State, owner: Address, interactor: Address) -> Ac
 
     Ok(HandlerResult::NewState(state))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use futures::executor::LocalPool;
+
+    use crate::{
+        error::ContractError,
+        state::{Role, State},
+        testing::{address, new_state, state},
+    };
+
+    use super::{add, remove};
+
+    // admin and manager hold the roles allowed to manage the interactors,
+    // eligible is an allowed interactor itself
+    fn managed_state() -> State {
+        let mut state = state();
+        state
+            .roles
+            .insert(address("admin"), HashSet::from([Role::Admin]));
+        state
+            .roles
+            .insert(address("manager"), HashSet::from([Role::AllowlistManager]));
+        state.allowed_interactors.insert(address("eligible"));
+        state
+    }
+
+    #[test]
+    fn owner_admins_and_allowlist_managers_manage_interactors() {
+        let mut pool = LocalPool::new();
+
+        for caller in ["owner", "admin", "manager"] {
+            let state = new_state(pool.run_until(add(
+                managed_state(),
+                address("owner"),
+                address(caller),
+                address("bob"),
+            )));
+            assert!(state.allowed_interactors.contains(&address("bob")));
+
+            let state = new_state(pool.run_until(remove(
+                state,
+                address("owner"),
+                address(caller),
+                address("bob"),
+            )));
+            assert!(!state.allowed_interactors.contains(&address("bob")));
+        }
+    }
+
+    #[test]
+    fn other_callers_cannot_manage_interactors() {
+        let mut pool = LocalPool::new();
+
+        for caller in ["eligible", "alice"] {
+            let result = pool.run_until(add(
+                managed_state(),
+                address("owner"),
+                address(caller),
+                address("bob"),
+            ));
+            assert_eq!(result.err(), Some(ContractError::Forbidden));
+
+            let result = pool.run_until(remove(
+                managed_state(),
+                address("owner"),
+                address(caller),
+                address("eligible"),
+            ));
+            assert_eq!(result.err(), Some(ContractError::Forbidden));
+        }
+    }
+
+    #[test]
+    fn interactors_are_added_and_removed_once() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(add(
+            managed_state(),
+            address("owner"),
+            address("owner"),
+            address("eligible"),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyInteractor(address("eligible")))
+        );
+
+        let result = pool.run_until(remove(
+            managed_state(),
+            address("owner"),
+            address("owner"),
+            address("bob"),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidInteractor(address("bob")))
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use bundlr_contracts_shared::{Address, BlockHeight};
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{JailRecord, Role, State},
+};
+
+use super::{rewards::settle_bundler, roles::has_role};
+
+// Jails the bundler for a milder offense, it does not earn rewards and cannot
+// withdraw until it unjails after the cooldown
+pub async fn jail(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    current_height: u128,
+    bundler: Address,
+    reason: String,
+) -> ActionResult {
+    if caller != owner
+        && !has_role(&state, &caller, Role::Admin)
+        && state.validators_contract.as_ref() != Some(&caller)
+    {
+        return Err(ContractError::Forbidden);
+    }
+
+    if reason.trim().is_empty() {
+        return Err(ContractError::MissingReason);
+    }
+
+    settle_bundler(&mut state, &bundler, current_height);
+
+    let entry = match state.bundlers.get_mut(&bundler) {
+        Some(entry) => entry,
+        None => {
+            return Err(ContractError::InvalidBundler(bundler));
+        }
+    };
+
+    if entry.jailed_until.is_some() {
+        return Err(ContractError::AlreadyJailed(bundler));
+    }
+
+    if entry.is_active() {
+        state.active_stake -= entry.weighted_stake() + entry.delegated;
+    }
+
+    let jailed_until = BlockHeight(current_height + state.jail_cooldown as u128);
+    entry.jailed_until = Some(jailed_until);
+
+    state
+        .jail_history
+        .entry(bundler)
+        .or_default()
+        .push(JailRecord {
+            jailed_at: BlockHeight(current_height),
+            jailed_until,
+            reason,
+            unjailed_at: None,
+        });
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn unjail(mut state: State, caller: Address, current_height: u128) -> ActionResult {
+    // Moves the reward index snapshot past the period the bundler was jailed
+    settle_bundler(&mut state, &caller, current_height);
+
+    let bundler = match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler,
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    };
+
+    match bundler.jailed_until {
+        Some(jailed_until) if *jailed_until > current_height => {
+            return Err(ContractError::BundlerJailed(jailed_until));
+        }
+        Some(_) => {}
+        None => {
+            return Err(ContractError::NotJailed(caller));
+        }
+    }
+
+    bundler.jailed_until = None;
+
+    if bundler.is_active() {
+        state.active_stake += bundler.weighted_stake() + bundler.delegated;
+    }
+
+    if let Some(record) = state
+        .jail_history
+        .get_mut(&caller)
+        .and_then(|history| history.last_mut())
+    {
+        record.unjailed_at = Some(BlockHeight(current_height));
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn jail_history(mut state: State, address: Address) -> ActionResult {
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::JailHistory(
+        state.jail_history.remove(&address).unwrap_or_default(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        action::ActionResult,
+        actions::{leave, token::mock::take_transfers, withdraw},
+        error::ContractError,
+        state::{Role, State},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{jail, unjail};
+
+    fn jail_as(state: State, caller: &str, height: u128) -> ActionResult {
+        let mut pool = LocalPool::new();
+
+        pool.run_until(jail(
+            state,
+            address("owner"),
+            address(caller),
+            height,
+            address("b1"),
+            "missed receipts".to_string(),
+        ))
+    }
+
+    fn staked_state() -> State {
+        let mut state = state();
+        state.validators_contract = Some(address("validators"));
+        state
+            .roles
+            .insert(address("admin"), HashSet::from([Role::Admin]));
+        state
+            .roles
+            .insert(address("manager"), HashSet::from([Role::AllowlistManager]));
+        join(&mut state, "b1", bundler(100, 1));
+        state
+    }
+
+    #[test]
+    fn owner_admins_and_validators_contract_can_jail() {
+        for caller in ["owner", "admin", "validators"] {
+            let state = new_state(jail_as(staked_state(), caller, 100));
+
+            assert_eq!(
+                state.bundlers[&address("b1")].jailed_until,
+                Some(BlockHeight(820))
+            );
+            assert_eq!(state.active_stake, Amount::ZERO);
+        }
+
+        for caller in ["manager", "b1"] {
+            assert_eq!(
+                jail_as(staked_state(), caller, 100).err(),
+                Some(ContractError::Forbidden)
+            );
+        }
+    }
+
+    #[test]
+    fn owner_can_jail_without_validators_contract() {
+        let mut state = staked_state();
+        state.validators_contract = None;
+
+        assert!(jail_as(state.clone(), "owner", 100).is_ok());
+        assert_eq!(
+            jail_as(state, "validators", 100).err(),
+            Some(ContractError::Forbidden)
+        );
+    }
+
+    #[test]
+    fn jail_requires_a_reason_and_a_free_bundler() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(jail(
+            staked_state(),
+            address("owner"),
+            address("owner"),
+            100,
+            address("b1"),
+            " ".to_string(),
+        ));
+        assert_eq!(result.err(), Some(ContractError::MissingReason));
+
+        let state = new_state(jail_as(staked_state(), "owner", 100));
+        assert_eq!(
+            jail_as(state, "owner", 200).err(),
+            Some(ContractError::AlreadyJailed(address("b1")))
+        );
+    }
+
+    #[test]
+    fn jailed_bundler_withdraws_only_after_unjailing() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(leave(staked_state(), address("b1"), 100, 10_000)));
+        let state = new_state(jail_as(state, "validators", 100));
+
+        let result = pool.run_until(withdraw(state.clone(), address("b1"), 200, 24_000));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::BundlerJailed(BlockHeight(820)))
+        );
+
+        let result = pool.run_until(unjail(state.clone(), address("b1"), 819));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::BundlerJailed(BlockHeight(820)))
+        );
+
+        let state = new_state(pool.run_until(unjail(state, address("b1"), 820)));
+        assert_eq!(state.bundlers[&address("b1")].jailed_until, None);
+        // Still leaving, so it does not start earning rewards again
+        assert_eq!(state.active_stake, Amount::ZERO);
+        assert_eq!(
+            state.jail_history[&address("b1")][0].unjailed_at,
+            Some(BlockHeight(820))
+        );
+
+        let state = new_state(pool.run_until(withdraw(state, address("b1"), 820, 98_400)));
+        assert!(!state.bundlers.contains_key(&address("b1")));
+        assert_eq!(take_transfers().len(), 1);
+    }
+
+    #[test]
+    fn unjailed_bundler_is_active_again() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(jail_as(staked_state(), "owner", 100));
+        let state = new_state(pool.run_until(unjail(state, address("b1"), 820)));
+
+        assert!(state.bundlers[&address("b1")].is_active());
+        assert_eq!(state.active_stake, Amount::from(100));
+
+        let result = pool.run_until(unjail(state, address("b1"), 830));
+        assert_eq!(result.err(), Some(ContractError::NotJailed(address("b1"))));
+    }
+}
@@ -1,37 +1,25 @@
-use std::str::FromStr;
-
-use bundlr_contracts_shared::{
-    contract_utils::js_imports::{Contract, SmartWeave},
-    Address, Amount,
-};
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsValue;
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
 
 use crate::{
-    action::ActionResult, contract_utils::{handler_result::HandlerResult, js_imports::log}, error::ContractError,
-    state::State,
+    action::ActionResult,
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Bundler, Profile, Role, State, STAKE_WEIGHT_PRECISION},
 };
 
-#[derive(Serialize)]
-struct Input {
-    function: String,
-    from: Address,
-    to: Address,
-    amount: Amount,
-}
-
-#[derive(Debug, Deserialize)]
-struct Result {
-    #[serde(rename = "type")]
-    result_type: String,
-}
-
-pub async fn join(mut state: State) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+use super::{profile::validate_profile, roles::has_role, token};
 
-    if !state.allowed_interactors.contains(&caller) {
+#[allow(clippy::too_many_arguments)]
+pub async fn join(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    stake: Option<Amount>,
+    profile: Option<Profile>,
+    tier: Option<String>,
+    token: Option<Address>,
+) -> ActionResult {
+    if !has_role(&state, &caller, Role::BundlerEligible) {
         return Err(ContractError::Forbidden);
     }
 
@@ -39,28 +27,165 @@ pub async fn join(mut state: State) -> ActionResult {
         return Err(ContractError::AlreadyJoined(caller));
     }
 
-    let result = SmartWeave::write(
-        &state.token.to_string(),
-        JsValue::from_serde(&Input {
-            function: "transferFrom".to_string(),
-            from: caller.clone(),
-            to: Address::from_str(&Contract::id())
-                .map_err(|err| ContractError::ParseError(err.to_string()))?,
-            amount: state.stake,
-        })
-        .unwrap(),
-    )
-    .await;
+    // Stake in the primary token is recorded without a token
+    let stake_token = token.filter(|token| *token != state.token);
+    let stake_weight = state.stake_weight(stake_token.as_ref())?;
 
-    log(&format!("{:?}", result));
+    // Required stake is in the primary token, the default stake is the
+    // smallest amount of the stake token worth at least as much
+    let required_stake = state.required_stake(tier.as_deref())?;
+    let stake = stake.unwrap_or_else(|| {
+        Amount::from(
+            (*required_stake * STAKE_WEIGHT_PRECISION as u128).div_ceil(stake_weight as u128),
+        )
+    });
 
-    let result: Result = result.into_serde().unwrap();
+    let mut bundler = Bundler {
+        stake,
+        stake_token,
+        stake_weight,
+        joined: BlockHeight(current_block_height),
+        leaving: None,
+        leaving_until: None,
+        unbonding: Default::default(),
+        unbonding_until: Default::default(),
+        jailed_until: None,
+        liveness: Default::default(),
+        profile: None,
+        tier,
+        delegated: Amount::ZERO,
+        reward_index: state.reward_index,
+        delegator_reward_index: 0,
+        legacy: false,
+    };
 
-    if result.result_type != "ok" {
-        return Err(ContractError::TransferFailed);
+    if bundler.weighted_stake() < required_stake {
+        return Err(ContractError::InsufficientStake(required_stake));
     }
 
-    state.bundlers.insert(caller, None);
+    bundler.profile = profile.map(validate_profile).transpose()?;
+
+    token::transfer_to_contract(bundler.token(&state.token), caller.clone(), stake).await?;
+
+    // Delegations left from before the bundler withdrew count again
+    let delegated = state
+        .delegations
+        .get(&caller)
+        .map(|delegations| {
+            delegations
+                .values()
+                .fold(Amount::ZERO, |total, delegation| total + delegation.amount)
+        })
+        .unwrap_or(Amount::ZERO);
+
+    bundler.delegated = delegated;
+    state.active_stake += bundler.weighted_stake() + delegated;
+
+    state.bundlers.insert(caller, bundler);
 
     Ok(HandlerResult::NewState(state))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        action::ActionResult,
+        actions::token::mock::{contract_address, take_transfers},
+        error::ContractError,
+        state::{Delegation, Role, State},
+        testing::{address, bundler, join as joined, new_state, state},
+    };
+
+    use super::join;
+
+    fn join_as(state: State, caller: &str) -> ActionResult {
+        let mut pool = LocalPool::new();
+
+        pool.run_until(join(state, address(caller), 100, None, None, None, None))
+    }
+
+    #[test]
+    fn eligible_address_joins_with_the_required_stake() {
+        let mut state = state();
+        state.allowed_interactors.insert(address("b1"));
+
+        let state = new_state(join_as(state, "b1"));
+
+        let bundler = &state.bundlers[&address("b1")];
+        assert_eq!(bundler.stake, Amount::from(100));
+        assert_eq!(bundler.joined, BlockHeight(100));
+        assert_eq!(state.active_stake, Amount::from(100));
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                address("b1"),
+                contract_address(),
+                Amount::from(100)
+            )]
+        );
+    }
+
+    #[test]
+    fn address_without_bundler_eligibility_cannot_join() {
+        let mut state = state();
+        // Other roles do not make an address eligible
+        state.roles.insert(
+            address("b1"),
+            HashSet::from([Role::Admin, Role::AllowlistManager]),
+        );
+
+        assert_eq!(join_as(state, "b1").err(), Some(ContractError::Forbidden));
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn bundler_eligibility_is_not_kept_in_roles() {
+        let mut state = state();
+        state
+            .roles
+            .insert(address("b1"), HashSet::from([Role::BundlerEligible]));
+
+        assert_eq!(join_as(state, "b1").err(), Some(ContractError::Forbidden));
+    }
+
+    #[test]
+    fn bundler_cannot_join_twice() {
+        let mut state = state();
+        state.allowed_interactors.insert(address("b1"));
+        joined(&mut state, "b1", bundler(100, 1));
+
+        assert_eq!(
+            join_as(state, "b1").err(),
+            Some(ContractError::AlreadyJoined(address("b1")))
+        );
+    }
+
+    #[test]
+    fn delegations_left_count_again_when_rejoining() {
+        let mut state = state();
+        state.allowed_interactors.insert(address("b1"));
+        state.delegations.insert(
+            address("b1"),
+            [(
+                address("d1"),
+                Delegation {
+                    amount: Amount::from(50),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        let state = new_state(join_as(state, "b1"));
+
+        assert_eq!(state.bundlers[&address("b1")].delegated, Amount::from(50));
+        assert_eq!(state.active_stake, Amount::from(150));
+    }
+}
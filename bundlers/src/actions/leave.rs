@@ -1,31 +1,222 @@
-use bundlr_contracts_shared::{
-    contract_utils::js_imports::{Block, SmartWeave},
-    Address, BlockHeight,
-};
+use bundlr_contracts_shared::Address;
 
 use crate::{
     action::ActionResult, contract_utils::handler_result::HandlerResult, error::ContractError,
     state::State,
 };
 
-pub async fn leave(mut state: State) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+use super::rewards::settle_bundler;
+
+pub async fn leave(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+) -> ActionResult {
+    settle_bundler(&mut state, &caller, current_block_height);
+
+    let (leaving, leaving_until) = state.withdraw_maturity(current_block_height, current_timestamp);
 
     match state.bundlers.get_mut(&caller) {
-        Some(val @ None) => {
-            *val = Some(BlockHeight(
-                Block::height() as u128 + state.withdraw_delay as u128,
-            ))
-        }
-        Some(Some(block)) => {
-            return Err(ContractError::AlreadyLeaving(caller, *block));
+        Some(bundler) => match bundler.leaving {
+            None => {
+                bundler.leaving = Some(leaving);
+                bundler.leaving_until = leaving_until;
+                // Leaving bundler and its delegators stop earning rewards
+                if bundler.jailed_until.is_none() {
+                    state.active_stake -= bundler.weighted_stake() + bundler.delegated;
+                }
+            }
+            Some(block) => {
+                return Err(ContractError::AlreadyLeaving(caller, block));
+            }
+        },
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
         }
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn cancel_leave(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+) -> ActionResult {
+    // Moves the reward index snapshot past the period the bundler was leaving
+    settle_bundler(&mut state, &caller, current_block_height);
+
+    let bundler = match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler,
         None => {
             return Err(ContractError::InvalidBundler(caller));
         }
+    };
+
+    let withdraw_allowed_in_block = match bundler.leaving {
+        Some(block) => block,
+        None => {
+            return Err(ContractError::NotLeaving(caller));
+        }
+    };
+
+    // Once the stake can be withdrawn the bundler is considered gone,
+    // it has to withdraw and join again.
+    if bundler.can_withdraw(current_block_height, current_timestamp) {
+        return Err(ContractError::AlreadyWithdrawable(
+            withdraw_allowed_in_block,
+        ));
+    }
+
+    bundler.leaving = None;
+    bundler.leaving_until = None;
+    if bundler.is_active() {
+        state.active_stake += bundler.weighted_stake() + bundler.delegated;
     }
 
     Ok(HandlerResult::NewState(state))
 }
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        error::ContractError,
+        state::{State, WithdrawDelay},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{cancel_leave, leave};
+
+    fn leaving_state(withdraw_delay: WithdrawDelay) -> State {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        state.withdraw_delay = withdraw_delay;
+        join(&mut state, "b1", bundler(100, 1));
+
+        new_state(pool.run_until(leave(state, address("b1"), 100, 10_000)))
+    }
+
+    #[test]
+    fn leaving_bundler_stops_earning_rewards() {
+        let state = leaving_state(WithdrawDelay::Blocks(10));
+
+        let bundler = &state.bundlers[&address("b1")];
+        assert_eq!(bundler.leaving, Some(BlockHeight(110)));
+        assert_eq!(bundler.leaving_until, None);
+        assert_eq!(state.active_stake, Amount::ZERO);
+    }
+
+    #[test]
+    fn bundler_cannot_leave_twice() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(leave(
+            leaving_state(WithdrawDelay::Blocks(10)),
+            address("b1"),
+            105,
+            10_600,
+        ));
+
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyLeaving(
+                address("b1"),
+                BlockHeight(110)
+            ))
+        );
+    }
+
+    #[test]
+    fn leave_can_be_cancelled_until_the_bundler_can_withdraw() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(cancel_leave(
+            leaving_state(WithdrawDelay::Blocks(10)),
+            address("b1"),
+            109,
+            11_080,
+        )));
+
+        let bundler = &state.bundlers[&address("b1")];
+        assert_eq!(bundler.leaving, None);
+        assert_eq!(state.active_stake, Amount::from(100));
+    }
+
+    #[test]
+    fn leave_cannot_be_cancelled_once_the_bundler_can_withdraw() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(cancel_leave(
+            leaving_state(WithdrawDelay::Blocks(10)),
+            address("b1"),
+            110,
+            11_200,
+        ));
+
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyWithdrawable(BlockHeight(110)))
+        );
+    }
+
+    #[test]
+    fn duration_delay_is_compared_to_the_timestamp() {
+        let mut pool = LocalPool::new();
+
+        // Blocks have been slower than the average, the estimated height has
+        // passed but the duration has not
+        let state = new_state(pool.run_until(cancel_leave(
+            leaving_state(WithdrawDelay::Duration { seconds: 1200 }),
+            address("b1"),
+            120,
+            11_199,
+        )));
+        assert_eq!(state.bundlers[&address("b1")].leaving, None);
+
+        let result = pool.run_until(cancel_leave(
+            leaving_state(WithdrawDelay::Duration { seconds: 1200 }),
+            address("b1"),
+            105,
+            11_200,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyWithdrawable(BlockHeight(110)))
+        );
+    }
+
+    #[test]
+    fn jailed_bundler_stays_inactive_after_cancelling() {
+        let mut pool = LocalPool::new();
+
+        let mut state = leaving_state(WithdrawDelay::Blocks(10));
+        state.bundlers.get_mut(&address("b1")).unwrap().jailed_until = Some(BlockHeight(900));
+
+        let state = new_state(pool.run_until(cancel_leave(state, address("b1"), 105, 10_600)));
+
+        assert_eq!(state.active_stake, Amount::ZERO);
+    }
+
+    #[test]
+    fn only_leaving_bundlers_can_cancel() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+
+        let result = pool.run_until(cancel_leave(state.clone(), address("b1"), 100, 10_000));
+        assert_eq!(result.err(), Some(ContractError::NotLeaving(address("b1"))));
+
+        let result = pool.run_until(cancel_leave(state, address("b2"), 100, 10_000));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidBundler(address("b2")))
+        );
+    }
+}
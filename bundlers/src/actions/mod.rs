@@ -1,10 +1,27 @@
+pub mod config;
+pub mod delegation;
+pub mod force_remove;
+pub mod heartbeat;
 pub mod interactors;
+pub mod jail;
 mod join;
 mod leave;
+mod profile;
+mod stake;
+mod sync_slashed;
+mod token;
+pub mod validators;
 mod withdraw;
 
 pub mod queries;
+pub mod rewards;
+pub mod roles;
+pub mod stake_tokens;
+pub mod tiers;
 
 pub use join::join;
-pub use leave::leave;
+pub use leave::{cancel_leave, leave};
+pub use profile::update_profile;
+pub use stake::{decrease_stake, increase_stake, withdraw_unbonded};
+pub use sync_slashed::sync_slashed;
 pub use withdraw::withdraw;
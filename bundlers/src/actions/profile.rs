@@ -0,0 +1,205 @@
+use bundlr_contracts_shared::Address;
+
+use crate::{
+    action::ActionResult,
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Profile, State},
+};
+
+const MAX_URL_LENGTH: usize = 256;
+const MAX_CONTACT_LENGTH: usize = 256;
+
+/// Checks the profile and normalizes it into the form it is stored in.
+pub(super) fn validate_profile(mut profile: Profile) -> Result<Profile, ContractError> {
+    if !(profile.url.starts_with("https://") || profile.url.starts_with("http://"))
+        || profile.url.len() > MAX_URL_LENGTH
+    {
+        return Err(ContractError::InvalidProfile(format!(
+            "invalid url: {}",
+            profile.url
+        )));
+    }
+
+    if profile.currencies.is_empty() {
+        return Err(ContractError::InvalidProfile(
+            "at least one currency has to be supported".to_string(),
+        ));
+    }
+
+    // Currencies are matched case insensitively by the BundlersByCurrency query
+    profile.currencies = profile
+        .currencies
+        .into_iter()
+        .map(|currency| currency.trim().to_lowercase())
+        .collect();
+
+    if profile
+        .currencies
+        .iter()
+        .any(|currency| currency.is_empty())
+    {
+        return Err(ContractError::InvalidProfile(
+            "currency cannot be empty".to_string(),
+        ));
+    }
+
+    if profile.public_key.is_empty() {
+        return Err(ContractError::InvalidProfile(
+            "public key cannot be empty".to_string(),
+        ));
+    }
+
+    if profile.max_data_size == 0 {
+        return Err(ContractError::InvalidProfile(
+            "max data size has to be higher than zero".to_string(),
+        ));
+    }
+
+    match profile.contact.as_deref() {
+        Some("") => profile.contact = None,
+        Some(contact) if contact.len() > MAX_CONTACT_LENGTH => {
+            return Err(ContractError::InvalidProfile(format!(
+                "contact cannot be longer than {} characters",
+                MAX_CONTACT_LENGTH
+            )));
+        }
+        _ => {}
+    }
+
+    Ok(profile)
+}
+
+pub async fn update_profile(mut state: State, caller: Address, profile: Profile) -> ActionResult {
+    let profile = validate_profile(profile)?;
+
+    match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler.profile = Some(profile),
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::LocalPool;
+
+    use crate::{
+        error::ContractError,
+        state::Profile,
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{update_profile, validate_profile, MAX_CONTACT_LENGTH};
+
+    fn profile() -> Profile {
+        Profile {
+            url: "https://node1.example.com".to_string(),
+            currencies: ["arweave".to_string()].into_iter().collect(),
+            public_key: "key".to_string(),
+            max_data_size: 1000,
+            contact: None,
+        }
+    }
+
+    fn invalid(profile: Profile) -> bool {
+        matches!(
+            validate_profile(profile),
+            Err(ContractError::InvalidProfile(_))
+        )
+    }
+
+    #[test]
+    fn currencies_and_contact_are_normalized() {
+        let profile = validate_profile(Profile {
+            currencies: [" Arweave ".to_string(), "SOLANA".to_string()]
+                .into_iter()
+                .collect(),
+            contact: Some(String::new()),
+            ..profile()
+        })
+        .unwrap();
+
+        assert_eq!(
+            profile.currencies,
+            ["arweave".to_string(), "solana".to_string()]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(profile.contact, None);
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        assert!(invalid(Profile {
+            url: "node1.example.com".to_string(),
+            ..profile()
+        }));
+        assert!(invalid(Profile {
+            url: format!("https://{}", "a".repeat(256)),
+            ..profile()
+        }));
+        assert!(invalid(Profile {
+            currencies: Default::default(),
+            ..profile()
+        }));
+        assert!(invalid(Profile {
+            currencies: [" ".to_string()].into_iter().collect(),
+            ..profile()
+        }));
+        assert!(invalid(Profile {
+            public_key: String::new(),
+            ..profile()
+        }));
+        assert!(invalid(Profile {
+            max_data_size: 0,
+            ..profile()
+        }));
+        assert!(invalid(Profile {
+            contact: Some("a".repeat(MAX_CONTACT_LENGTH + 1)),
+            ..profile()
+        }));
+        assert!(!invalid(Profile {
+            contact: Some("a".repeat(MAX_CONTACT_LENGTH)),
+            ..profile()
+        }));
+    }
+
+    #[test]
+    fn bundler_can_replace_its_profile() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+
+        let state = new_state(pool.run_until(update_profile(state, address("b1"), profile())));
+        let state = new_state(pool.run_until(update_profile(
+            state,
+            address("b1"),
+            Profile {
+                url: "https://node2.example.com".to_string(),
+                ..profile()
+            },
+        )));
+
+        assert_eq!(
+            state.bundlers[&address("b1")].profile.as_ref().unwrap().url,
+            "https://node2.example.com"
+        );
+    }
+
+    #[test]
+    fn only_bundlers_have_profiles() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(update_profile(state(), address("b1"), profile()));
+
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidBundler(address("b1")))
+        );
+    }
+}
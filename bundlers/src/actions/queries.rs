@@ -1,9 +1,156 @@
-use crate::action::{ActionResult, QueryResponseMsg};
+use bundlr_contracts_shared::Address;
+
+use crate::action::{ActionResult, BundlerStatus, LeavingBundler, QueryResponseMsg};
 use crate::contract_utils::handler_result::HandlerResult::QueryResponse;
+use crate::error::ContractError;
 use crate::state::State;
 
 pub fn bunders(state: State) -> ActionResult {
-    Ok(QueryResponse(QueryResponseMsg::Bundlers(state.bundlers)))
+    Ok(QueryResponse(QueryResponseMsg::Bundlers(
+        state
+            .bundlers
+            .into_iter()
+            .map(|(address, bundler)| (address, bundler.leaving))
+            .collect(),
+    )))
+}
+pub fn bundler(mut state: State, address: Address) -> ActionResult {
+    let bundler = state
+        .bundlers
+        .remove(&address)
+        .ok_or(ContractError::InvalidBundler(address))?;
+
+    Ok(QueryResponse(QueryResponseMsg::Bundler(Box::new(bundler))))
+}
+// Returns the items sorted by key which come after start_after, which does not
+// have to be listed
+fn paginate<T, K: Ord>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> K,
+    start_after: Option<K>,
+    limit: Option<usize>,
+) -> Vec<T> {
+    items
+        .into_iter()
+        .skip_while(|item| match &start_after {
+            Some(start_after) => key(item) <= *start_after,
+            None => false,
+        })
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+// Active bundlers sorted by address
+pub fn active_bundlers(
+    state: State,
+    current_height: u128,
+    start_after: Option<Address>,
+    limit: Option<usize>,
+) -> ActionResult {
+    let mut bundlers = state
+        .bundlers
+        .iter()
+        .filter(|(_, bundler)| bundler.is_active() && !state.is_inactive(bundler, current_height))
+        .map(|(address, _)| address.clone())
+        .collect::<Vec<_>>();
+    bundlers.sort();
+
+    Ok(QueryResponse(QueryResponseMsg::ActiveBundlers(paginate(
+        bundlers,
+        |address| address.clone(),
+        start_after,
+        limit,
+    ))))
+}
+// Leaving bundlers sorted by the height they can withdraw in, then by address
+pub fn leaving_bundlers(
+    state: State,
+    current_height: u128,
+    current_timestamp: u64,
+    start_after: Option<Address>,
+    limit: Option<usize>,
+) -> ActionResult {
+    let mut bundlers = state
+        .bundlers
+        .into_iter()
+        .filter_map(|(address, bundler)| {
+            let unlock_time =
+                bundler.unlock_time(current_height, current_timestamp, state.average_block_time)?;
+            bundler.leaving.map(|withdraw_height| LeavingBundler {
+                address,
+                withdraw_height,
+                blocks_remaining: withdraw_height.saturating_sub(current_height),
+                unlock_time,
+            })
+        })
+        .collect::<Vec<_>>();
+    bundlers.sort_by(|a, b| (a.withdraw_height, &a.address).cmp(&(b.withdraw_height, &b.address)));
+
+    let start_after = match start_after {
+        Some(address) => Some(
+            bundlers
+                .iter()
+                .find(|bundler| bundler.address == address)
+                .map(|bundler| (bundler.withdraw_height, address.clone()))
+                .ok_or(ContractError::NotLeaving(address))?,
+        ),
+        None => None,
+    };
+
+    Ok(QueryResponse(QueryResponseMsg::LeavingBundlers(paginate(
+        bundlers,
+        |bundler| (bundler.withdraw_height, bundler.address.clone()),
+        start_after,
+        limit,
+    ))))
+}
+pub fn bundler_status(
+    state: State,
+    current_height: u128,
+    current_timestamp: u64,
+    address: Address,
+) -> ActionResult {
+    let status = match state.bundlers.get(&address) {
+        Some(bundler) => match (bundler.jailed_until, bundler.leaving) {
+            (Some(jailed_until), _) => BundlerStatus::Jailed(jailed_until),
+            (None, None) if state.is_inactive(bundler, current_height) => BundlerStatus::Inactive,
+            (None, None) => BundlerStatus::Active,
+            (None, Some(_)) if bundler.can_withdraw(current_height, current_timestamp) => {
+                BundlerStatus::Withdrawable
+            }
+            (None, Some(height)) => BundlerStatus::Leaving {
+                height,
+                // We can safely unwrap here, the bundler is leaving
+                unlock_time: bundler
+                    .unlock_time(current_height, current_timestamp, state.average_block_time)
+                    .unwrap(),
+            },
+        },
+        None => BundlerStatus::Unknown,
+    };
+
+    Ok(QueryResponse(QueryResponseMsg::BundlerStatus(status)))
+}
+// Only active bundlers are listed, the ones leaving are about to go away and
+// the inactive ones are not sending heartbeats
+pub fn bundlers_by_currency(state: State, current_height: u128, currency: String) -> ActionResult {
+    let currency = currency.to_lowercase();
+
+    Ok(QueryResponse(QueryResponseMsg::BundlersByCurrency(
+        state
+            .bundlers
+            .iter()
+            .filter(|(_, bundler)| {
+                bundler.is_active() && !state.is_inactive(bundler, current_height)
+            })
+            .filter_map(|(address, bundler)| {
+                bundler
+                    .profile
+                    .as_ref()
+                    .filter(|profile| profile.currencies.contains(&currency))
+                    .map(|profile| (address.clone(), profile.clone()))
+            })
+            .collect(),
+    )))
 }
 pub fn withdraw_delay(state: State) -> ActionResult {
     Ok(QueryResponse(QueryResponseMsg::WithdrawDelay(
@@ -16,8 +163,176 @@ pub fn stake(state: State) -> ActionResult {
 pub fn token(state: State) -> ActionResult {
     Ok(QueryResponse(QueryResponseMsg::Token(state.token)))
 }
+pub fn treasury(state: State) -> ActionResult {
+    Ok(QueryResponse(QueryResponseMsg::Treasury(state.treasury)))
+}
+pub fn validators_contract(state: State) -> ActionResult {
+    Ok(QueryResponse(QueryResponseMsg::ValidatorsContract(
+        state.validators_contract,
+    )))
+}
 pub fn allowed_interactors(state: State) -> ActionResult {
     Ok(QueryResponse(QueryResponseMsg::AllowedInteractors(
         state.allowed_interactors,
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Address, BlockHeight};
+
+    use crate::{
+        action::QueryResponseMsg,
+        contract_utils::handler_result::HandlerResult,
+        error::ContractError,
+        state::State,
+        testing::{address, bundler, join, state},
+    };
+
+    use super::{active_bundlers, leaving_bundlers, paginate};
+
+    fn page(start_after: Option<&str>, limit: Option<usize>) -> Vec<u32> {
+        paginate(
+            vec![("a", 1), ("b", 2), ("c", 3), ("d", 4)]
+                .into_iter()
+                .map(|(item, n)| (address(item), n))
+                .collect(),
+            |(address, _)| address.clone(),
+            start_after.map(address),
+            limit,
+        )
+        .into_iter()
+        .map(|(_, n)| n)
+        .collect()
+    }
+
+    fn active(state: State, start_after: Option<&str>, limit: Option<usize>) -> Vec<Address> {
+        match active_bundlers(state, 100, start_after.map(address), limit) {
+            Ok(HandlerResult::QueryResponse(QueryResponseMsg::ActiveBundlers(bundlers))) => {
+                bundlers
+            }
+            _ => panic!("expected active bundlers"),
+        }
+    }
+
+    #[test]
+    fn page_starts_after_the_given_item() {
+        assert_eq!(page(None, None), vec![1, 2, 3, 4]);
+        assert_eq!(page(Some("a"), Some(2)), vec![2, 3]);
+        assert_eq!(page(Some("c"), Some(2)), vec![4]);
+        assert_eq!(page(Some("d"), Some(2)), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn page_bounds() {
+        assert_eq!(page(None, Some(0)), Vec::<u32>::new());
+        assert_eq!(page(None, Some(usize::MAX)), vec![1, 2, 3, 4]);
+        // Start which is not listed is placed by its key
+        assert_eq!(page(Some("bb"), None), vec![3, 4]);
+        assert_eq!(page(Some(""), None), vec![1, 2, 3, 4]);
+        assert_eq!(page(Some("e"), None), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn active_bundlers_are_paged_in_address_order() {
+        let mut state = state();
+        for name in ["b3", "b1", "b4", "b2"] {
+            join(&mut state, name, bundler(100, 1));
+        }
+        let mut leaving = bundler(100, 1);
+        leaving.leaving = Some(BlockHeight(110));
+        state.bundlers.insert(address("b0"), leaving);
+
+        assert_eq!(
+            active(state.clone(), None, Some(3)),
+            vec![address("b1"), address("b2"), address("b3")]
+        );
+        assert_eq!(
+            active(state.clone(), Some("b3"), Some(3)),
+            vec![address("b4")]
+        );
+        assert_eq!(
+            active(state.clone(), Some("b4"), Some(3)),
+            Vec::<Address>::new()
+        );
+        // Leaving bundler is not listed, the page still follows its address
+        assert_eq!(active(state, Some("b0"), Some(1)), vec![address("b1")]);
+    }
+
+    #[test]
+    fn active_bundlers_page_after_a_removed_bundler() {
+        let mut state = state();
+        for name in ["b1", "b2", "b3", "b4"] {
+            join(&mut state, name, bundler(100, 1));
+        }
+
+        let first = active(state.clone(), None, Some(2));
+        assert_eq!(first, vec![address("b1"), address("b2")]);
+
+        // Last bundler of the page is force removed before the next call
+        state.bundlers.remove(&address("b2"));
+        assert_eq!(
+            active(state, Some("b2"), Some(2)),
+            vec![address("b3"), address("b4")]
+        );
+    }
+
+    #[test]
+    fn inactive_bundlers_are_not_listed_as_active() {
+        let mut state = state();
+        state.heartbeat_window = 10;
+        state.max_missed_heartbeats = 3;
+        join(&mut state, "b1", bundler(100, 1));
+        join(&mut state, "b2", bundler(100, 80));
+
+        // At height 100, b1 has missed 9 windows and b2 only 2
+        assert_eq!(active(state, None, None), vec![address("b2")]);
+    }
+
+    #[test]
+    fn leaving_bundlers_are_paged_by_withdraw_height() {
+        let mut state = state();
+        for (name, height) in [("b1", 130), ("b2", 110), ("b3", 120), ("b4", 110)] {
+            let mut leaving = bundler(100, 1);
+            leaving.leaving = Some(BlockHeight(height));
+            state.bundlers.insert(address(name), leaving);
+        }
+
+        let page = |start_after: Option<&str>, limit| match leaving_bundlers(
+            state.clone(),
+            115,
+            10_000,
+            start_after.map(address),
+            limit,
+        ) {
+            Ok(HandlerResult::QueryResponse(QueryResponseMsg::LeavingBundlers(bundlers))) => {
+                bundlers
+                    .into_iter()
+                    .map(|bundler| {
+                        (
+                            bundler.address,
+                            *bundler.withdraw_height,
+                            bundler.blocks_remaining,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => panic!("expected leaving bundlers"),
+        };
+
+        assert_eq!(
+            page(None, Some(3)),
+            vec![
+                (address("b2"), 110, 0),
+                (address("b4"), 110, 0),
+                (address("b3"), 120, 5)
+            ]
+        );
+        assert_eq!(page(Some("b3"), Some(3)), vec![(address("b1"), 130, 15)]);
+        assert_eq!(page(Some("b1"), Some(3)), Vec::new());
+
+        // Height of a start which is no longer leaving is not known
+        let result = leaving_bundlers(state.clone(), 115, 10_000, Some(address("b5")), None);
+        assert_eq!(result.err(), Some(ContractError::NotLeaving(address("b5"))));
+    }
+}
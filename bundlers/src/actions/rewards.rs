@@ -0,0 +1,369 @@
+use bundlr_contracts_shared::{Address, Amount};
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::State,
+};
+
+use super::token;
+
+// Reward indexes are kept as fixed point numbers with this precision
+pub const REWARD_INDEX_PRECISION: u128 = 1_000_000_000_000;
+
+// Rewards earned by the given stake while the index grew by index_delta
+fn earned(stake: Amount, index_delta: u128) -> Amount {
+    Amount::from(
+        *stake / REWARD_INDEX_PRECISION * index_delta
+            + *stake % REWARD_INDEX_PRECISION * index_delta / REWARD_INDEX_PRECISION,
+    )
+}
+
+fn add_rewards(state: &mut State, address: &Address, amount: Amount) {
+    if amount != Amount::ZERO {
+        *state.rewards.entry(address.clone()).or_default() += amount;
+    }
+}
+
+// Settles rewards earned since the last settlement, the ones of an inactive
+// bundler and its delegators are forfeited to the treasury
+pub(super) fn settle_bundler(state: &mut State, address: &Address, current_height: u128) {
+    let reward_index = state.reward_index;
+    let inactive = match state.bundlers.get(address) {
+        Some(bundler) => state.is_inactive(bundler, current_height),
+        None => return,
+    };
+    // We can safely unwrap here, the bundler was checked above
+    let bundler = state.bundlers.get_mut(address).unwrap();
+
+    let index_delta = reward_index - bundler.reward_index;
+    bundler.reward_index = reward_index;
+
+    if !bundler.is_active() || index_delta == 0 {
+        return;
+    }
+
+    let bundler_rewards = earned(bundler.weighted_stake(), index_delta);
+    let delegated_rewards = earned(bundler.delegated, index_delta);
+
+    if inactive {
+        if let Some(treasury) = state.treasury.clone() {
+            add_rewards(state, &treasury, bundler_rewards + delegated_rewards);
+        }
+        return;
+    }
+
+    if bundler.delegated != Amount::ZERO {
+        bundler.delegator_reward_index +=
+            *delegated_rewards * REWARD_INDEX_PRECISION / *bundler.delegated;
+    }
+
+    add_rewards(state, address, bundler_rewards);
+}
+
+// Settles rewards the delegation has earned, bundler has to be settled first
+pub(super) fn settle_delegation(state: &mut State, bundler: &Address, delegator: &Address) {
+    let delegator_reward_index = match state.bundlers.get(bundler) {
+        Some(bundler) => bundler.delegator_reward_index,
+        None => return,
+    };
+    let delegation = match state
+        .delegations
+        .get_mut(bundler)
+        .and_then(|delegations| delegations.get_mut(delegator))
+    {
+        Some(delegation) => delegation,
+        None => return,
+    };
+
+    let rewards = earned(
+        delegation.amount,
+        delegator_reward_index - delegation.reward_index,
+    );
+    delegation.reward_index = delegator_reward_index;
+
+    add_rewards(state, delegator, rewards);
+}
+
+// Settles rewards of the bundler and all its delegations before it is removed
+pub(super) fn settle_all(state: &mut State, bundler: &Address, current_height: u128) {
+    settle_bundler(state, bundler, current_height);
+
+    let delegators = state
+        .delegations
+        .get(bundler)
+        .map(|delegations| delegations.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    for delegator in delegators {
+        settle_delegation(state, bundler, &delegator);
+    }
+
+    // Delegation index restarts from zero if the bundler joins again
+    if let Some(delegations) = state.delegations.get_mut(bundler) {
+        for delegation in delegations.values_mut() {
+            delegation.reward_index = 0;
+        }
+    }
+}
+
+// Settles everything the address has earned, as a bundler and as a delegator
+fn settle_address(state: &mut State, address: &Address, current_height: u128) {
+    settle_bundler(state, address, current_height);
+
+    let bundlers = state
+        .delegations
+        .iter()
+        .filter(|(_, delegations)| delegations.contains_key(address))
+        .map(|(bundler, _)| bundler.clone())
+        .collect::<Vec<_>>();
+    for bundler in bundlers {
+        settle_bundler(state, &bundler, current_height);
+        settle_delegation(state, &bundler, address);
+    }
+}
+
+/// Distributes the deposited rewards to the active bundlers and their
+/// delegators, pro rata to their stake.
+pub async fn deposit_rewards(mut state: State, caller: Address, amount: Amount) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    if state.active_stake == Amount::ZERO {
+        return Err(ContractError::NoActiveStake);
+    }
+
+    let index_delta = (*amount)
+        .checked_mul(REWARD_INDEX_PRECISION)
+        .ok_or_else(|| ContractError::RuntimeError("reward amount is too high".to_string()))?
+        / *state.active_stake;
+
+    token::transfer_to_contract(&state.token, caller, amount).await?;
+
+    state.reward_index += index_delta;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn claim_rewards(
+    mut state: State,
+    caller: Address,
+    current_height: u128,
+) -> ActionResult {
+    settle_address(&mut state, &caller, current_height);
+
+    let rewards = state
+        .rewards
+        .remove(&caller)
+        .ok_or(ContractError::NoRewards)?;
+
+    token::transfer(&state.token, caller, rewards).await?;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn pending_rewards(mut state: State, current_height: u128, address: Address) -> ActionResult {
+    settle_address(&mut state, &address, current_height);
+
+    Ok(HandlerResult::QueryResponse(
+        QueryResponseMsg::PendingRewards(state.rewards.remove(&address).unwrap_or(Amount::ZERO)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Address, Amount};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        action::{ActionResult, QueryResponseMsg},
+        actions::{
+            delegation::delegate,
+            heartbeat::heartbeat,
+            increase_stake, leave,
+            token::mock::{contract_address, take_transfers},
+        },
+        contract_utils::handler_result::HandlerResult,
+        error::ContractError,
+        state::{HeartbeatStats, State},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{claim_rewards, deposit_rewards, pending_rewards};
+
+    fn deposit(state: State, amount: u128) -> ActionResult {
+        let mut pool = LocalPool::new();
+
+        pool.run_until(deposit_rewards(
+            state,
+            address("depositor"),
+            Amount::from(amount),
+        ))
+    }
+
+    fn pending(state: &State, address: &str) -> u128 {
+        match pending_rewards(state.clone(), 100, Address::try_from(address).unwrap()) {
+            Ok(HandlerResult::QueryResponse(QueryResponseMsg::PendingRewards(amount))) => *amount,
+            _ => panic!("expected pending rewards"),
+        }
+    }
+
+    #[test]
+    fn rewards_cannot_be_deposited_without_active_stake() {
+        assert_eq!(
+            deposit(state(), 100).err(),
+            Some(ContractError::NoActiveStake)
+        );
+
+        // Leaving bundlers do not count
+        let mut pool = LocalPool::new();
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+        let state = new_state(pool.run_until(leave(state, address("b1"), 100, 10_000)));
+
+        assert_eq!(
+            deposit(state, 100).err(),
+            Some(ContractError::NoActiveStake)
+        );
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn rewards_are_shared_pro_rata_to_the_stake() {
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+        join(&mut state, "b2", bundler(300, 1));
+
+        let state = new_state(deposit(state, 1000));
+
+        assert_eq!(pending(&state, "b1"), 250);
+        assert_eq!(pending(&state, "b2"), 750);
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                address("depositor"),
+                contract_address(),
+                Amount::from(1000)
+            )]
+        );
+    }
+
+    #[test]
+    fn rounding_dust_stays_in_the_contract() {
+        let mut state = state();
+        join(&mut state, "b1", bundler(1, 1));
+        join(&mut state, "b2", bundler(2, 1));
+
+        let state = new_state(deposit(state, 10));
+
+        // 10 / 3 per staked token, rounded down for each bundler
+        assert_eq!(pending(&state, "b1"), 3);
+        assert_eq!(pending(&state, "b2"), 6);
+    }
+
+    #[test]
+    fn stake_change_applies_to_later_deposits_only() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+        join(&mut state, "b2", bundler(100, 1));
+
+        let state = new_state(deposit(state, 200));
+        let state =
+            new_state(pool.run_until(increase_stake(state, address("b1"), 100, Amount::from(200))));
+        let state = new_state(deposit(state, 400));
+
+        assert_eq!(pending(&state, "b1"), 100 + 300);
+        assert_eq!(pending(&state, "b2"), 100 + 100);
+    }
+
+    #[test]
+    fn delegators_earn_the_share_of_their_delegation() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+        join(&mut state, "b2", bundler(100, 1));
+        let state = new_state(deposit(state, 200));
+        let state = new_state(pool.run_until(delegate(
+            state,
+            address("d1"),
+            100,
+            address("b1"),
+            Amount::from(200),
+        )));
+        let state = new_state(deposit(state, 400));
+
+        // Delegation made after the first deposit only earns from the second
+        assert_eq!(pending(&state, "b1"), 100 + 100);
+        assert_eq!(pending(&state, "b2"), 100 + 100);
+        assert_eq!(pending(&state, "d1"), 200);
+    }
+
+    #[test]
+    fn claimed_rewards_are_transferred_once() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        join(&mut state, "b1", bundler(100, 1));
+        let state = new_state(deposit(state, 100));
+        take_transfers();
+
+        let state = new_state(pool.run_until(claim_rewards(state, address("b1"), 100)));
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                contract_address(),
+                address("b1"),
+                Amount::from(100)
+            )]
+        );
+
+        let result = pool.run_until(claim_rewards(state, address("b1"), 100));
+        assert_eq!(result.err(), Some(ContractError::NoRewards));
+    }
+
+    #[test]
+    fn inactive_bundler_forfeits_rewards_to_the_treasury() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        state.treasury = Some(address("treasury"));
+        state.heartbeat_window = 10;
+        state.max_missed_heartbeats = 3;
+        join(&mut state, "b1", bundler(100, 1));
+        join(&mut state, "b2", bundler(100, 100));
+        let state = new_state(pool.run_until(delegate(
+            state,
+            address("d1"),
+            100,
+            address("b1"),
+            Amount::from(200),
+        )));
+        let state = new_state(deposit(state, 400));
+
+        // b1 has not sent a heartbeat since joining at height 1
+        assert_eq!(pending(&state, "b1"), 0);
+        assert_eq!(pending(&state, "d1"), 0);
+        assert_eq!(pending(&state, "b2"), 100);
+
+        // Forfeited rewards are credited to the treasury when the bundler is
+        // settled, heartbeat settles it and makes it earn again
+        let state = new_state(pool.run_until(heartbeat(
+            state,
+            address("b1"),
+            100,
+            1,
+            HeartbeatStats { uploaded_bytes: 0 },
+        )));
+        let state = new_state(deposit(state, 400));
+
+        assert_eq!(pending(&state, "b1"), 100);
+        assert_eq!(pending(&state, "d1"), 200);
+        assert_eq!(pending(&state, "treasury"), 300);
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::{HashMap, HashSet};
+
+use bundlr_contracts_shared::Address;
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Role, State},
+};
+
+pub fn has_role(state: &State, address: &Address, role: Role) -> bool {
+    match role {
+        // Bundler eligibility is kept in the allowed interactors list
+        Role::BundlerEligible => state.allowed_interactors.contains(address),
+        role => state
+            .roles
+            .get(address)
+            .map(|roles| roles.contains(&role))
+            .unwrap_or(false),
+    }
+}
+
+pub async fn grant(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    role: Role,
+    address: Address,
+) -> ActionResult {
+    if caller != owner {
+        return Err(ContractError::Forbidden);
+    }
+
+    if has_role(&state, &address, role) {
+        return Err(ContractError::AlreadyHasRole(address, role));
+    }
+
+    match role {
+        Role::BundlerEligible => {
+            state.allowed_interactors.insert(address);
+        }
+        role => {
+            state.roles.entry(address).or_default().insert(role);
+        }
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn revoke(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    role: Role,
+    address: Address,
+) -> ActionResult {
+    if caller != owner {
+        return Err(ContractError::Forbidden);
+    }
+
+    if !has_role(&state, &address, role) {
+        return Err(ContractError::MissingRole(address, role));
+    }
+
+    match role {
+        Role::BundlerEligible => {
+            state.allowed_interactors.remove(&address);
+        }
+        role => {
+            // We can safely unwrap here, the role was checked above
+            let roles = state.roles.get_mut(&address).unwrap();
+            roles.remove(&role);
+            if roles.is_empty() {
+                state.roles.remove(&address);
+            }
+        }
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn roles(state: State) -> ActionResult {
+    let mut roles: HashMap<Address, HashSet<Role>> = state.roles;
+    for address in state.allowed_interactors {
+        roles
+            .entry(address)
+            .or_default()
+            .insert(Role::BundlerEligible);
+    }
+
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::Roles(roles)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use futures::executor::LocalPool;
+
+    use crate::{
+        error::ContractError,
+        state::Role,
+        testing::{address, new_state, state},
+    };
+
+    use super::{grant, revoke};
+
+    #[test]
+    fn only_owner_grants_and_revokes_roles() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        state
+            .roles
+            .insert(address("admin"), HashSet::from([Role::Admin]));
+
+        for caller in ["admin", "alice"] {
+            let result = pool.run_until(grant(
+                state.clone(),
+                address("owner"),
+                address(caller),
+                Role::AllowlistManager,
+                address("bob"),
+            ));
+            assert_eq!(result.err(), Some(ContractError::Forbidden));
+
+            let result = pool.run_until(revoke(
+                state.clone(),
+                address("owner"),
+                address(caller),
+                Role::Admin,
+                address("admin"),
+            ));
+            assert_eq!(result.err(), Some(ContractError::Forbidden));
+        }
+    }
+
+    #[test]
+    fn bundler_eligibility_is_granted_through_the_allowed_interactors() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(grant(
+            state(),
+            address("owner"),
+            address("owner"),
+            Role::BundlerEligible,
+            address("bob"),
+        )));
+        assert!(state.allowed_interactors.contains(&address("bob")));
+        assert!(state.roles.is_empty());
+
+        let result = pool.run_until(grant(
+            state.clone(),
+            address("owner"),
+            address("owner"),
+            Role::BundlerEligible,
+            address("bob"),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyHasRole(
+                address("bob"),
+                Role::BundlerEligible
+            ))
+        );
+
+        let state = new_state(pool.run_until(revoke(
+            state,
+            address("owner"),
+            address("owner"),
+            Role::BundlerEligible,
+            address("bob"),
+        )));
+        assert!(state.allowed_interactors.is_empty());
+    }
+
+    #[test]
+    fn revoking_the_last_role_removes_the_address() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(grant(
+            state(),
+            address("owner"),
+            address("owner"),
+            Role::Admin,
+            address("bob"),
+        )));
+        assert_eq!(state.roles[&address("bob")], HashSet::from([Role::Admin]));
+
+        let state = new_state(pool.run_until(revoke(
+            state,
+            address("owner"),
+            address("owner"),
+            Role::Admin,
+            address("bob"),
+        )));
+        assert!(state.roles.is_empty());
+
+        let result = pool.run_until(revoke(
+            state,
+            address("owner"),
+            address("owner"),
+            Role::Admin,
+            address("bob"),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::MissingRole(address("bob"), Role::Admin))
+        );
+    }
+}
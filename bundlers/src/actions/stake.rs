@@ -0,0 +1,455 @@
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+
+use crate::{
+    action::ActionResult,
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{is_matured, State},
+};
+
+use super::{rewards::settle_bundler, token};
+
+pub async fn increase_stake(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    amount: Amount,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    match state.bundlers.get(&caller) {
+        Some(bundler) if bundler.leaving.is_some() => {
+            return Err(ContractError::AlreadyLeaving(
+                caller.clone(),
+                bundler.leaving.unwrap(),
+            ));
+        }
+        Some(bundler) if bundler.jailed_until.is_some() => {
+            return Err(ContractError::BundlerJailed(bundler.jailed_until.unwrap()));
+        }
+        Some(bundler) => {
+            // Stake is topped up in the token it was deposited in
+            token::transfer_to_contract(bundler.token(&state.token), caller.clone(), amount)
+                .await?;
+        }
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    }
+
+    settle_bundler(&mut state, &caller, current_block_height);
+
+    // We can safely unwrap here, the bundler was checked above
+    let bundler = state.bundlers.get_mut(&caller).unwrap();
+    state.active_stake -= bundler.weighted_stake();
+    bundler.stake += amount;
+    state.active_stake += bundler.weighted_stake();
+
+    Ok(HandlerResult::NewState(state))
+}
+
+// Moves part of the stake into an unbonding tranche which can be withdrawn
+// after the withdraw delay
+pub async fn decrease_stake(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+    amount: Amount,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    settle_bundler(&mut state, &caller, current_block_height);
+
+    let (maturity_height, matures_until) =
+        state.withdraw_maturity(current_block_height, current_timestamp);
+    let required_stake = match state.bundlers.get(&caller) {
+        Some(bundler) => state.required_stake(bundler.tier.as_deref())?,
+        None => state.stake,
+    };
+    let bundler = match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler,
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    };
+
+    if let Some(block) = bundler.leaving {
+        return Err(ContractError::AlreadyLeaving(caller, block));
+    }
+
+    if let Some(block) = bundler.jailed_until {
+        return Err(ContractError::BundlerJailed(block));
+    }
+
+    // Bundler has to keep the stake required by its tier while active
+    if bundler.stake < amount || bundler.weighted(bundler.stake - amount) < required_stake {
+        return Err(ContractError::InsufficientStake(required_stake));
+    }
+
+    state.active_stake -= bundler.weighted_stake();
+    bundler.stake -= amount;
+    *bundler.unbonding.entry(maturity_height).or_default() += amount;
+    // Tranches ending up with the same height after a withdraw delay change
+    // are merged, they mature when both of them would have
+    if let Some(matures_until) = matures_until {
+        let until = bundler
+            .unbonding_until
+            .entry(maturity_height)
+            .or_insert(matures_until);
+        *until = (*until).max(matures_until);
+    }
+    state.active_stake += bundler.weighted_stake();
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn withdraw_unbonded(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+    maturity_height: BlockHeight,
+) -> ActionResult {
+    let bundler = match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler,
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    };
+
+    if let Some(block) = bundler.jailed_until {
+        return Err(ContractError::BundlerJailed(block));
+    }
+
+    if !bundler.unbonding.contains_key(&maturity_height) {
+        return Err(ContractError::TrancheNotFound(maturity_height));
+    }
+
+    let matures_until = bundler.unbonding_until.get(&maturity_height).copied();
+    if !is_matured(
+        maturity_height,
+        matures_until,
+        current_block_height,
+        current_timestamp,
+    ) {
+        return Err(ContractError::TrancheNotMatured(maturity_height));
+    }
+
+    // We can safely unwrap here, the tranche was checked above
+    let amount = bundler.unbonding.remove(&maturity_height).unwrap();
+    bundler.unbonding_until.remove(&maturity_height);
+
+    token::transfer(bundler.token(&state.token), caller, amount).await?;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        actions::token::mock::{contract_address, take_transfers},
+        error::ContractError,
+        state::{State, WithdrawDelay},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::{decrease_stake, withdraw_unbonded};
+
+    // Bundler b1 with 300 staked, 200 above the minimum
+    fn staked_state() -> State {
+        let mut state = state();
+        join(&mut state, "b1", bundler(300, 1));
+        state
+    }
+
+    #[test]
+    fn decreased_stake_unbonds_until_maturity() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(decrease_stake(
+            staked_state(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(50),
+        )));
+        let state = new_state(pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(50),
+        )));
+        let state = new_state(pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            105,
+            10_600,
+            Amount::from(100),
+        )));
+
+        let bundler = &state.bundlers[&address("b1")];
+        assert_eq!(bundler.stake, Amount::from(100));
+        assert!(bundler.is_active());
+        // Decreases in the same block end up in the same tranche
+        assert_eq!(
+            bundler.unbonding.clone().into_iter().collect::<Vec<_>>(),
+            vec![
+                (BlockHeight(110), Amount::from(100)),
+                (BlockHeight(115), Amount::from(100))
+            ]
+        );
+        assert_eq!(state.active_stake, Amount::from(100));
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn stake_cannot_be_decreased_below_the_minimum() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(decrease_stake(
+            staked_state(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(201),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(100)))
+        );
+
+        let result = pool.run_until(decrease_stake(
+            staked_state(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(301),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(100)))
+        );
+
+        let result = pool.run_until(decrease_stake(
+            staked_state(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::ZERO,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AmountMustBeHigherThanZero)
+        );
+    }
+
+    #[test]
+    fn leaving_bundler_cannot_decrease_stake() {
+        let mut pool = LocalPool::new();
+
+        let mut state = staked_state();
+        state.bundlers.get_mut(&address("b1")).unwrap().leaving = Some(BlockHeight(105));
+
+        let result = pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(50),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AlreadyLeaving(
+                address("b1"),
+                BlockHeight(105)
+            ))
+        );
+    }
+
+    #[test]
+    fn tranche_is_withdrawn_once_matured() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(decrease_stake(
+            staked_state(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(150),
+        )));
+
+        let result = pool.run_until(withdraw_unbonded(
+            state.clone(),
+            address("b1"),
+            109,
+            11_080,
+            BlockHeight(110),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::TrancheNotMatured(BlockHeight(110)))
+        );
+
+        let state = new_state(pool.run_until(withdraw_unbonded(
+            state,
+            address("b1"),
+            110,
+            11_200,
+            BlockHeight(110),
+        )));
+        assert!(state.bundlers[&address("b1")].unbonding.is_empty());
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                contract_address(),
+                address("b1"),
+                Amount::from(150)
+            )]
+        );
+
+        let result = pool.run_until(withdraw_unbonded(
+            state,
+            address("b1"),
+            120,
+            12_400,
+            BlockHeight(110),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::TrancheNotFound(BlockHeight(110)))
+        );
+    }
+
+    #[test]
+    fn unknown_tranche_cannot_be_withdrawn() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(decrease_stake(
+            staked_state(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(150),
+        )));
+
+        let result = pool.run_until(withdraw_unbonded(
+            state,
+            address("b1"),
+            200,
+            24_000,
+            BlockHeight(105),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::TrancheNotFound(BlockHeight(105)))
+        );
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn tranche_unbonded_with_duration_delay_matures_at_the_timestamp() {
+        let mut pool = LocalPool::new();
+
+        let mut state = staked_state();
+        state.withdraw_delay = WithdrawDelay::Duration { seconds: 1200 };
+
+        let state = new_state(pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(150),
+        )));
+        let bundler = &state.bundlers[&address("b1")];
+        assert_eq!(bundler.unbonding[&BlockHeight(110)], Amount::from(150));
+        assert_eq!(bundler.unbonding_until[&BlockHeight(110)], 11_200);
+
+        // Blocks have been faster than the average, the estimated height has
+        // passed but the duration has not
+        let result = pool.run_until(withdraw_unbonded(
+            state.clone(),
+            address("b1"),
+            115,
+            11_199,
+            BlockHeight(110),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::TrancheNotMatured(BlockHeight(110)))
+        );
+
+        let state = new_state(pool.run_until(withdraw_unbonded(
+            state,
+            address("b1"),
+            105,
+            11_200,
+            BlockHeight(110),
+        )));
+        let bundler = &state.bundlers[&address("b1")];
+        assert!(bundler.unbonding.is_empty());
+        assert!(bundler.unbonding_until.is_empty());
+        assert_eq!(take_transfers().len(), 1);
+    }
+
+    #[test]
+    fn tranche_is_withdrawn_in_the_stake_token() {
+        let mut pool = LocalPool::new();
+
+        // 400 of a token worth half of the primary one
+        let mut state = state();
+        let mut b1 = bundler(400, 1);
+        b1.stake_token = Some(address("other"));
+        b1.stake_weight = 5_000;
+        join(&mut state, "b1", b1);
+
+        // Minimum stake applies to the weighted stake
+        let result = pool.run_until(decrease_stake(
+            state.clone(),
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(201),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(100)))
+        );
+
+        let state = new_state(pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(200),
+        )));
+        assert_eq!(state.active_stake, Amount::from(100));
+
+        let state = new_state(pool.run_until(withdraw_unbonded(
+            state,
+            address("b1"),
+            110,
+            11_200,
+            BlockHeight(110),
+        )));
+        assert!(state.bundlers[&address("b1")].unbonding.is_empty());
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("other"),
+                contract_address(),
+                address("b1"),
+                Amount::from(200)
+            )]
+        );
+    }
+}
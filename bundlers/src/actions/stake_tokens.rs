@@ -0,0 +1,201 @@
+use bundlr_contracts_shared::Address;
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Role, StakeToken, State},
+};
+
+use super::roles::has_role;
+
+pub async fn set_stake_token(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    token: Address,
+    weight: u32,
+) -> ActionResult {
+    if caller != owner && !has_role(&state, &caller, Role::Admin) {
+        return Err(ContractError::Forbidden);
+    }
+
+    // Primary token always has the full weight
+    if token == state.token {
+        return Err(ContractError::InvalidStakeToken(token));
+    }
+
+    if weight == 0 {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    // Bundlers already staked in the token keep the weight they joined with,
+    // so that the active stake stays consistent with the rewards paid out.
+    state.stake_tokens.insert(token, StakeToken { weight });
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn remove_stake_token(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    token: Address,
+) -> ActionResult {
+    if caller != owner && !has_role(&state, &caller, Role::Admin) {
+        return Err(ContractError::Forbidden);
+    }
+
+    // Bundlers already staked in the token can still top up, unbond and
+    // withdraw it, only new bundlers cannot join with it.
+    if state.stake_tokens.remove(&token).is_none() {
+        return Err(ContractError::InvalidStakeToken(token));
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn stake_tokens(state: State) -> ActionResult {
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::StakeTokens(
+        state.stake_tokens,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bundlr_contracts_shared::Amount;
+    use futures::executor::LocalPool;
+
+    use crate::{
+        actions::join::join,
+        error::ContractError,
+        state::{Role, StakeToken, State},
+        testing::{address, bundler, join as joined, new_state, state},
+    };
+
+    use super::{remove_stake_token, set_stake_token};
+
+    // Token "other" is worth half of the primary token, b1 is staked in it
+    fn token_state() -> State {
+        let mut state = state();
+        state
+            .roles
+            .insert(address("admin"), HashSet::from([Role::Admin]));
+        state.allowed_interactors.insert(address("b2"));
+        state
+            .stake_tokens
+            .insert(address("other"), StakeToken { weight: 5_000 });
+        let mut b1 = bundler(200, 1);
+        b1.stake_token = Some(address("other"));
+        b1.stake_weight = 5_000;
+        joined(&mut state, "b1", b1);
+        state
+    }
+
+    #[test]
+    fn stake_tokens_are_managed_by_owner_and_admins() {
+        let mut pool = LocalPool::new();
+
+        for caller in ["owner", "admin"] {
+            let state = new_state(pool.run_until(set_stake_token(
+                token_state(),
+                address("owner"),
+                address(caller),
+                address("third"),
+                20_000,
+            )));
+            assert_eq!(state.stake_tokens[&address("third")].weight, 20_000);
+        }
+
+        let result = pool.run_until(set_stake_token(
+            token_state(),
+            address("owner"),
+            address("b1"),
+            address("third"),
+            20_000,
+        ));
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+
+        let result = pool.run_until(remove_stake_token(
+            token_state(),
+            address("owner"),
+            address("b1"),
+            address("other"),
+        ));
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+    }
+
+    #[test]
+    fn primary_token_and_zero_weight_are_rejected() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(set_stake_token(
+            token_state(),
+            address("owner"),
+            address("owner"),
+            address("token"),
+            5_000,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidStakeToken(address("token")))
+        );
+
+        let result = pool.run_until(set_stake_token(
+            token_state(),
+            address("owner"),
+            address("owner"),
+            address("third"),
+            0,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::AmountMustBeHigherThanZero)
+        );
+
+        let result = pool.run_until(remove_stake_token(
+            token_state(),
+            address("owner"),
+            address("owner"),
+            address("third"),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidStakeToken(address("third")))
+        );
+    }
+
+    #[test]
+    fn removed_token_stays_staked_but_cannot_be_joined_with() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(remove_stake_token(
+            token_state(),
+            address("owner"),
+            address("owner"),
+            address("other"),
+        )));
+        assert!(state.stake_tokens.is_empty());
+
+        let b1 = &state.bundlers[&address("b1")];
+        assert_eq!(b1.stake_token, Some(address("other")));
+        assert_eq!(b1.weighted_stake(), Amount::from(100));
+        assert_eq!(state.active_stake, Amount::from(100));
+
+        let result = pool.run_until(join(
+            state,
+            address("b2"),
+            100,
+            Some(Amount::from(200)),
+            None,
+            None,
+            Some(address("other")),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidStakeToken(address("other")))
+        );
+    }
+}
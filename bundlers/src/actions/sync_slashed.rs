@@ -0,0 +1,416 @@
+use bundlr_contracts_shared::{contract_utils::js_imports::SmartWeave, Amount, TransactionId};
+
+use crate::{
+    action::ActionResult, contract_utils::handler_result::HandlerResult, error::ContractError,
+    state::State,
+};
+
+use super::{
+    rewards::settle_all,
+    token,
+    validators::{parse_validators_state, ValidatorsState, Vote, Voting},
+};
+
+pub async fn sync_slashed(state: State, current_block_height: u128) -> ActionResult {
+    let validators_contract = state
+        .validators_contract
+        .clone()
+        .ok_or(ContractError::ValidatorsContractNotSet)?;
+
+    let validators_state =
+        parse_validators_state(SmartWeave::read_contract_state(&validators_contract).await)?;
+
+    apply_slashed(state, current_block_height, validators_state).await
+}
+
+// Applies the concluded slash proposals of the validators contract which have
+// not been applied yet
+async fn apply_slashed(
+    mut state: State,
+    current_block_height: u128,
+    validators_state: ValidatorsState,
+) -> ActionResult {
+    let treasury = state
+        .treasury
+        .clone()
+        .ok_or(ContractError::TreasuryNotSet)?;
+
+    let proposals = validators_state
+        .slash_proposals
+        .into_iter()
+        .filter(|(tx, (_, _, _, _, voting))| {
+            matches!(
+                voting,
+                Voting::Closed {
+                    final_vote: Vote::For
+                }
+            ) && !state.applied_slash_proposals.contains(tx)
+        })
+        .map(|(tx, _)| tx)
+        .collect::<Vec<TransactionId>>();
+
+    if proposals.is_empty() {
+        return Err(ContractError::NothingToSlash);
+    }
+
+    // Recorded even if the bundler has withdrawn, so a rejoin is not slashed
+    state.applied_slash_proposals.extend(proposals);
+
+    let bundler = validators_state.bundler;
+
+    // Rewards earned before the slash are kept
+    settle_all(&mut state, &bundler, current_block_height);
+    if let Some(slashed) = state.bundlers.get(&bundler) {
+        if slashed.is_active() {
+            state.active_stake -= slashed.weighted_stake() + slashed.delegated;
+        }
+    }
+
+    // Unbonding tranches and delegations are slashed as well
+    let (stake_token, slashed_stake) = state
+        .bundlers
+        .remove(&bundler)
+        .map(|slashed| {
+            let stake_token = slashed.token(&state.token).clone();
+            let stake = slashed
+                .unbonding
+                .into_values()
+                .fold(slashed.stake, |total, amount| total + amount);
+            (stake_token, stake)
+        })
+        .unwrap_or((state.token.clone(), Amount::ZERO));
+    let slashed_delegations = state
+        .delegations
+        .remove(&bundler)
+        .unwrap_or_default()
+        .into_values()
+        .fold(Amount::ZERO, |total, delegation| {
+            total + delegation.amount + delegation.unbonding
+        });
+
+    // Nothing is left to slash if the bundler has already withdrawn
+    if slashed_stake == Amount::ZERO && slashed_delegations == Amount::ZERO {
+        return Ok(HandlerResult::NewState(state));
+    }
+
+    // Delegations are always in the primary token, the stake can be in any of
+    // the stake tokens
+    if stake_token == state.token {
+        token::transfer(&state.token, treasury, slashed_stake + slashed_delegations).await?;
+    } else {
+        if slashed_stake != Amount::ZERO {
+            token::transfer(&stake_token, treasury.clone(), slashed_stake).await?;
+        }
+        if slashed_delegations != Amount::ZERO {
+            token::transfer(&state.token, treasury, slashed_delegations).await?;
+        }
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+    use serde_json::json;
+
+    use crate::{
+        actions::{
+            token::mock::{take_transfers, FAILING_TOKEN},
+            validators::ValidatorsState,
+        },
+        error::ContractError,
+        state::{Delegation, StakeToken, State},
+        testing::{address, bundler, join, new_state, state},
+    };
+
+    use super::apply_slashed;
+
+    fn slashable_state() -> State {
+        let mut state = state();
+        state.validators_contract = Some(address("validators"));
+        state.treasury = Some(address("treasury"));
+        join(&mut state, "b1", bundler(1000, 1));
+        state
+    }
+
+    // Validators contract state as it is serialized, with the final vote of
+    // each proposal or None if the voting is still open
+    fn validators_state(proposals: &[(&str, Option<&str>)]) -> ValidatorsState {
+        let slash_proposals = proposals
+            .iter()
+            .map(|(id, final_vote)| {
+                let voting = match final_vote {
+                    Some(final_vote) => json!({
+                        "Closed": { "votes": {}, "final_vote": final_vote }
+                    }),
+                    None => json!({ "Open": { "v1": "for" } }),
+                };
+                (
+                    id.to_string(),
+                    json!([{ "id": id }, "v1", 10, format!("proposal_{}", id), voting]),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        let state = json!({
+            "bundler": "b1",
+            "bundlersContract": "bundlers_contract",
+            "slashProposals": slash_proposals,
+        });
+
+        serde_json::from_str(&state.to_string()).unwrap()
+    }
+
+    #[test]
+    fn each_concluded_proposal_is_applied_once() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(apply_slashed(
+            slashable_state(),
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        )));
+
+        assert!(!state.bundlers.contains_key(&address("b1")));
+        assert!(state.applied_slash_proposals.contains(&address("tx1")));
+        assert_eq!(state.active_stake, Amount::ZERO);
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                address("bundlers_contract"),
+                address("treasury"),
+                Amount::from(1000)
+            )]
+        );
+
+        let result = pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        ));
+
+        assert_eq!(result.err(), Some(ContractError::NothingToSlash));
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn open_and_rejected_proposals_are_not_applied() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(apply_slashed(
+            slashable_state(),
+            100,
+            validators_state(&[("tx1", None), ("tx2", Some("against"))]),
+        ));
+
+        assert_eq!(result.err(), Some(ContractError::NothingToSlash));
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn unbonding_stake_and_delegations_are_transferred_to_treasury() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        state.validators_contract = Some(address("validators"));
+        state.treasury = Some(address("treasury"));
+        let mut slashed = bundler(1000, 1);
+        slashed.unbonding = BTreeMap::from([(BlockHeight(20), Amount::from(200))]);
+        slashed.delegated = Amount::from(300);
+        join(&mut state, "b1", slashed);
+        state.delegations.insert(
+            address("b1"),
+            HashMap::from([(
+                address("d1"),
+                Delegation {
+                    amount: Amount::from(300),
+                    unbonding: Amount::from(50),
+                    withdrawable_at: Some(BlockHeight(20)),
+                    withdrawable_until: None,
+                    reward_index: 0,
+                },
+            )]),
+        );
+
+        let state = new_state(pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        )));
+
+        assert!(!state.delegations.contains_key(&address("b1")));
+        assert_eq!(state.active_stake, Amount::ZERO);
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                address("bundlers_contract"),
+                address("treasury"),
+                Amount::from(1550)
+            )]
+        );
+    }
+
+    #[test]
+    fn every_delegation_to_the_slashed_bundler_is_slashed() {
+        let mut pool = LocalPool::new();
+
+        let mut state = slashable_state();
+        let delegation = |amount: u128, unbonding: u128| Delegation {
+            amount: Amount::from(amount),
+            unbonding: Amount::from(unbonding),
+            withdrawable_at: (unbonding != 0).then_some(BlockHeight(20)),
+            withdrawable_until: None,
+            reward_index: 0,
+        };
+        state.bundlers.get_mut(&address("b1")).unwrap().delegated = Amount::from(500);
+        join(&mut state, "b2", bundler(100, 1));
+        state.bundlers.get_mut(&address("b2")).unwrap().delegated = Amount::from(40);
+        state.active_stake += Amount::from(540);
+        state.delegations.insert(
+            address("b1"),
+            HashMap::from([
+                (address("d1"), delegation(300, 0)),
+                (address("d2"), delegation(200, 100)),
+            ]),
+        );
+        state.delegations.insert(
+            address("b2"),
+            HashMap::from([(address("d1"), delegation(40, 10))]),
+        );
+
+        let state = new_state(pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        )));
+
+        // Delegations to other bundlers are not affected
+        assert!(!state.delegations.contains_key(&address("b1")));
+        assert_eq!(
+            state.delegations[&address("b2")][&address("d1")].amount,
+            Amount::from(40)
+        );
+        assert_eq!(
+            state.delegations[&address("b2")][&address("d1")].unbonding,
+            Amount::from(10)
+        );
+        assert_eq!(state.active_stake, Amount::from(140));
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                address("bundlers_contract"),
+                address("treasury"),
+                Amount::from(1600)
+            )]
+        );
+    }
+
+    #[test]
+    fn stake_in_other_token_is_transferred_separately_from_delegations() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        state.validators_contract = Some(address("validators"));
+        state.treasury = Some(address("treasury"));
+        state
+            .stake_tokens
+            .insert(address("other"), StakeToken { weight: 5000 });
+        let mut slashed = bundler(1000, 1);
+        slashed.stake_token = Some(address("other"));
+        slashed.stake_weight = 5000;
+        slashed.delegated = Amount::from(300);
+        join(&mut state, "b1", slashed);
+        state.delegations.insert(
+            address("b1"),
+            HashMap::from([(
+                address("d1"),
+                Delegation {
+                    amount: Amount::from(300),
+                    ..Default::default()
+                },
+            )]),
+        );
+
+        let state = new_state(pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        )));
+
+        assert_eq!(state.active_stake, Amount::ZERO);
+        assert_eq!(
+            take_transfers(),
+            vec![
+                (
+                    address("other"),
+                    address("bundlers_contract"),
+                    address("treasury"),
+                    Amount::from(1000)
+                ),
+                (
+                    address("token"),
+                    address("bundlers_contract"),
+                    address("treasury"),
+                    Amount::from(300)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn proposal_against_withdrawn_bundler_is_recorded_without_transfer() {
+        let mut pool = LocalPool::new();
+
+        let mut state = slashable_state();
+        state.bundlers.clear();
+        state.active_stake = Amount::ZERO;
+
+        let state = new_state(pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        )));
+
+        assert!(state.applied_slash_proposals.contains(&address("tx1")));
+        assert!(take_transfers().is_empty());
+    }
+
+    #[test]
+    fn failed_transfer_fails_the_sync() {
+        let mut pool = LocalPool::new();
+
+        let mut state = slashable_state();
+        state.token = address(FAILING_TOKEN);
+
+        let result = pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        ));
+
+        assert_eq!(result.err(), Some(ContractError::TransferFailed));
+    }
+
+    #[test]
+    fn treasury_is_required() {
+        let mut pool = LocalPool::new();
+
+        let mut state = slashable_state();
+        state.treasury = None;
+
+        let result = pool.run_until(apply_slashed(
+            state,
+            100,
+            validators_state(&[("tx1", Some("for"))]),
+        ));
+
+        assert_eq!(result.err(), Some(ContractError::TreasuryNotSet));
+    }
+}
@@ -0,0 +1,342 @@
+use bundlr_contracts_shared::{Address, Amount};
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::{Role, State, Tier},
+};
+
+use super::roles::has_role;
+
+pub async fn set_tier(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    name: String,
+    capacity: u64,
+    stake: Amount,
+) -> ActionResult {
+    if caller != owner && !has_role(&state, &caller, Role::Admin) {
+        return Err(ContractError::Forbidden);
+    }
+
+    if name.is_empty() {
+        return Err(ContractError::InvalidTier(name));
+    }
+
+    if stake == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    // Bundlers already in the tier keep their stake, a higher required stake is
+    // enforced when they change the tier or unbond.
+    state.tiers.insert(name, Tier { capacity, stake });
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn remove_tier(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    name: String,
+) -> ActionResult {
+    if caller != owner && !has_role(&state, &caller, Role::Admin) {
+        return Err(ContractError::Forbidden);
+    }
+
+    if !state.tiers.contains_key(&name) {
+        return Err(ContractError::InvalidTier(name));
+    }
+
+    if state
+        .bundlers
+        .values()
+        .any(|bundler| bundler.tier.as_ref() == Some(&name))
+    {
+        return Err(ContractError::TierInUse(name));
+    }
+
+    state.tiers.remove(&name);
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub async fn change_tier(mut state: State, caller: Address, tier: Option<String>) -> ActionResult {
+    let required_stake = state.required_stake(tier.as_deref())?;
+
+    let bundler = match state.bundlers.get_mut(&caller) {
+        Some(bundler) => bundler,
+        None => {
+            return Err(ContractError::InvalidBundler(caller));
+        }
+    };
+
+    if let Some(block) = bundler.leaving {
+        return Err(ContractError::AlreadyLeaving(caller, block));
+    }
+
+    // Stake has to be increased first when moving to a more demanding tier
+    if bundler.weighted_stake() < required_stake {
+        return Err(ContractError::InsufficientStake(required_stake));
+    }
+
+    bundler.tier = tier;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn tiers(state: State) -> ActionResult {
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::Tiers(
+        state.tiers,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bundlr_contracts_shared::Amount;
+    use futures::executor::LocalPool;
+
+    use crate::{
+        actions::{decrease_stake, increase_stake, join},
+        error::ContractError,
+        state::{Role, State, Tier},
+        testing::{address, bundler, join as joined, new_state, state},
+    };
+
+    use super::{change_tier, remove_tier, set_tier};
+
+    // Tiers above and below the minimum stake of 100
+    fn tiered_state() -> State {
+        let mut state = state();
+        state.allowed_interactors.insert(address("b1"));
+        state.tiers.insert(
+            "large".to_string(),
+            Tier {
+                capacity: 1000,
+                stake: Amount::from(500),
+            },
+        );
+        state.tiers.insert(
+            "small".to_string(),
+            Tier {
+                capacity: 10,
+                stake: Amount::from(50),
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn joining_a_tier_requires_its_stake() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(join(
+            tiered_state(),
+            address("b1"),
+            100,
+            Some(Amount::from(499)),
+            None,
+            Some("large".to_string()),
+            None,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(500)))
+        );
+
+        // Stake defaults to the one required by the tier
+        let state = new_state(pool.run_until(join(
+            tiered_state(),
+            address("b1"),
+            100,
+            None,
+            None,
+            Some("large".to_string()),
+            None,
+        )));
+        assert_eq!(state.bundlers[&address("b1")].stake, Amount::from(500));
+    }
+
+    #[test]
+    fn minimum_stake_applies_to_tiers_below_it() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(join(
+            tiered_state(),
+            address("b1"),
+            100,
+            Some(Amount::from(50)),
+            None,
+            Some("small".to_string()),
+            None,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(100)))
+        );
+    }
+
+    #[test]
+    fn unknown_tier_cannot_be_joined() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(join(
+            tiered_state(),
+            address("b1"),
+            100,
+            None,
+            None,
+            Some("medium".to_string()),
+            None,
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidTier("medium".to_string()))
+        );
+    }
+
+    #[test]
+    fn stake_has_to_be_increased_before_moving_to_a_higher_tier() {
+        let mut pool = LocalPool::new();
+
+        let mut state = tiered_state();
+        joined(&mut state, "b1", bundler(100, 1));
+
+        let result = pool.run_until(change_tier(
+            state.clone(),
+            address("b1"),
+            Some("large".to_string()),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(500)))
+        );
+
+        let state =
+            new_state(pool.run_until(increase_stake(state, address("b1"), 100, Amount::from(400))));
+        let state =
+            new_state(pool.run_until(change_tier(state, address("b1"), Some("large".to_string()))));
+        assert_eq!(
+            state.bundlers[&address("b1")].tier.as_deref(),
+            Some("large")
+        );
+
+        // Tier stake is kept while in the tier
+        let result = pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(1),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(500)))
+        );
+    }
+
+    #[test]
+    fn raised_tier_stake_applies_to_later_changes_only() {
+        let mut pool = LocalPool::new();
+
+        let mut state = tiered_state();
+        let mut large = bundler(600, 1);
+        large.tier = Some("large".to_string());
+        joined(&mut state, "b1", large);
+
+        let state = new_state(pool.run_until(set_tier(
+            state,
+            address("owner"),
+            address("owner"),
+            "large".to_string(),
+            1000,
+            Amount::from(700),
+        )));
+        assert!(state.bundlers[&address("b1")].is_active());
+
+        let result = pool.run_until(decrease_stake(
+            state,
+            address("b1"),
+            100,
+            10_000,
+            Amount::from(1),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InsufficientStake(Amount::from(700)))
+        );
+    }
+
+    #[test]
+    fn tiers_are_managed_by_owner_and_admins() {
+        let mut pool = LocalPool::new();
+
+        let mut state = tiered_state();
+        state
+            .roles
+            .insert(address("admin"), HashSet::from([Role::Admin]));
+
+        for caller in ["owner", "admin"] {
+            let result = pool.run_until(set_tier(
+                state.clone(),
+                address("owner"),
+                address(caller),
+                "medium".to_string(),
+                100,
+                Amount::from(200),
+            ));
+            assert!(result.is_ok());
+        }
+
+        let result = pool.run_until(set_tier(
+            state.clone(),
+            address("owner"),
+            address("b1"),
+            "medium".to_string(),
+            100,
+            Amount::from(200),
+        ));
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+
+        let result = pool.run_until(remove_tier(
+            state,
+            address("owner"),
+            address("b1"),
+            "small".to_string(),
+        ));
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+    }
+
+    #[test]
+    fn tier_in_use_cannot_be_removed() {
+        let mut pool = LocalPool::new();
+
+        let mut state = tiered_state();
+        let mut large = bundler(500, 1);
+        large.tier = Some("large".to_string());
+        joined(&mut state, "b1", large);
+
+        let result = pool.run_until(remove_tier(
+            state.clone(),
+            address("owner"),
+            address("owner"),
+            "large".to_string(),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::TierInUse("large".to_string()))
+        );
+
+        let state = new_state(pool.run_until(remove_tier(
+            state,
+            address("owner"),
+            address("owner"),
+            "small".to_string(),
+        )));
+        assert!(!state.tiers.contains_key("small"));
+    }
+}
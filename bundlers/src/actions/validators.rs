@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use bundlr_contracts_shared::{
+    contract_utils::js_imports::{Contract, SmartWeave},
+    Address, BlockHeight, TransactionId,
+};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+use crate::{
+    action::{ActionResult, QueryResponseMsg},
+    contract_utils::handler_result::HandlerResult,
+    error::ContractError,
+    state::State,
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Vote {
+    For,
+    Against,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum Voting {
+    Open(IgnoredAny),
+    Closed { final_vote: Vote },
+}
+
+// Subset of the validators contract state, only the parts needed by this contract
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorsState {
+    pub bundler: Address,
+    pub bundlers_contract: Address,
+    // key: TransactionId, ID of the tx that proposal is referring to
+    // value: proposal, proposer, block height of the proposal, proposal tx ID, voting
+    pub slash_proposals: HashMap<TransactionId, (IgnoredAny, Address, u128, TransactionId, Voting)>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashProposal {
+    // ID of the tx that proposal is referring to
+    pub id: TransactionId,
+    pub proposed_by: Address,
+    pub proposed_at: BlockHeight,
+    // None while the voting is open
+    pub final_vote: Option<Vote>,
+    // whether the slash has already been applied by SyncSlashed
+    pub applied: bool,
+}
+
+// Parses the validators contract state as returned by readContractState
+pub fn parse_validators_state(state: JsValue) -> Result<ValidatorsState, ContractError> {
+    state.into_serde().map_err(|err| {
+        ContractError::RuntimeError(format!("Failed to read validators contract state: {}", err))
+    })
+}
+
+pub async fn set_validators_contract(
+    mut state: State,
+    owner: Address,
+    caller: Address,
+    validators_contract: Address,
+) -> ActionResult {
+    if caller != owner {
+        return Err(ContractError::Forbidden);
+    }
+
+    // Validators contract has to point back to this contract, otherwise its
+    // verdicts are about bundlers registered elsewhere.
+    let validators_state =
+        parse_validators_state(SmartWeave::read_contract_state(&validators_contract).await)?;
+    if *validators_state.bundlers_contract != Contract::id() {
+        return Err(ContractError::InvalidValidatorsContract(
+            validators_contract,
+        ));
+    }
+
+    state.validators_contract = Some(validators_contract);
+
+    Ok(HandlerResult::NewState(state))
+}
+
+/// Lists slash proposals of the validators contract affecting the bundler,
+/// open ones first, each sorted by the height they were proposed at.
+pub async fn slash_proposals(state: State, bundler: Address) -> ActionResult {
+    let validators_contract = state
+        .validators_contract
+        .ok_or(ContractError::ValidatorsContractNotSet)?;
+
+    let validators_state =
+        parse_validators_state(SmartWeave::read_contract_state(&validators_contract).await)?;
+
+    // Each validators contract watches a single bundler
+    if validators_state.bundler != bundler {
+        return Ok(HandlerResult::QueryResponse(
+            QueryResponseMsg::SlashProposals {
+                open: Vec::new(),
+                concluded: Vec::new(),
+            },
+        ));
+    }
+
+    let (mut open, mut concluded): (Vec<_>, Vec<_>) = validators_state
+        .slash_proposals
+        .into_iter()
+        .map(
+            |(id, (_, proposed_by, proposed_at, _, voting))| SlashProposal {
+                applied: state.applied_slash_proposals.contains(&id),
+                id,
+                proposed_by,
+                proposed_at: BlockHeight(proposed_at),
+                final_vote: match voting {
+                    Voting::Open(_) => None,
+                    Voting::Closed { final_vote } => Some(final_vote),
+                },
+            },
+        )
+        .partition(|proposal| proposal.final_vote.is_none());
+
+    open.sort_by(|a, b| (a.proposed_at, &a.id).cmp(&(b.proposed_at, &b.id)));
+    concluded.sort_by(|a, b| (a.proposed_at, &a.id).cmp(&(b.proposed_at, &b.id)));
+
+    Ok(HandlerResult::QueryResponse(
+        QueryResponseMsg::SlashProposals { open, concluded },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::LocalPool;
+    use serde_json::json;
+
+    use crate::{
+        error::ContractError,
+        testing::{address, state},
+    };
+
+    use super::{set_validators_contract, ValidatorsState, Vote, Voting};
+
+    #[test]
+    fn only_owner_sets_the_validators_contract() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(set_validators_contract(
+            state(),
+            address("owner"),
+            address("alice"),
+            address("validators"),
+        ));
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+    }
+
+    #[test]
+    fn full_validators_state_is_parsed() {
+        // State as written by the validators contract, fields this contract
+        // does not need are ignored
+        let state = json!({
+            "bundler": "b1",
+            "bundlersContract": "bundlers_contract",
+            "epoch": { "seq": "1", "tx": "epoch_tx", "height": "500" },
+            "epochDuration": 500,
+            "minimumStake": "10",
+            "token": "token",
+            "maxNumNominatedValidators": 10,
+            "validators": {},
+            "nominatedValidators": ["v1"],
+            "slashProposalLifetime": 500,
+            "unbondingEpochs": 2,
+            "slashProposals": {
+                "tx1": [
+                    { "id": "tx1", "size": 100, "fee": "1", "currency": "arweave",
+                      "block": 10, "validator": "v1", "signature": "sig" },
+                    "v1",
+                    510,
+                    "proposal_tx1",
+                    { "Closed": { "votes": { "v1": ["for", "10"] }, "final_vote": "for" } }
+                ],
+                "tx2": [
+                    { "id": "tx2" },
+                    "v1",
+                    520,
+                    "proposal_tx2",
+                    { "Open": { "v1": "against" } }
+                ]
+            },
+            "stakeSnapshots": {}
+        });
+
+        let state: ValidatorsState = serde_json::from_str(&state.to_string()).unwrap();
+
+        assert_eq!(state.bundler, address("b1"));
+        assert_eq!(state.bundlers_contract, address("bundlers_contract"));
+        let (_, proposed_by, proposed_at, _, voting) = &state.slash_proposals[&address("tx1")];
+        assert_eq!(*proposed_by, address("v1"));
+        assert_eq!(*proposed_at, 510);
+        assert!(matches!(
+            voting,
+            Voting::Closed {
+                final_vote: Vote::For
+            }
+        ));
+        let (_, _, _, _, voting) = &state.slash_proposals[&address("tx2")];
+        assert!(matches!(voting, Voting::Open(_)));
+    }
+}
@@ -1,70 +1,127 @@
-use bundlr_contracts_shared::{
-    contract_utils::js_imports::{log, Block, SmartWeave},
-    Address, Amount,
-};
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsValue;
+use bundlr_contracts_shared::Address;
 
 use crate::{
     action::ActionResult, contract_utils::handler_result::HandlerResult, error::ContractError,
     state::State,
 };
 
-#[derive(Serialize)]
-struct Input {
-    function: String,
-    to: Address,
-    amount: Amount,
-}
-
-#[derive(Debug, Deserialize)]
-struct Result {
-    #[serde(rename = "type")]
-    result_type: String,
-}
+use super::{rewards::settle_all, token};
 
-pub async fn withdraw(mut state: State) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
-
-    let withdraw_allowed_in_block = match state.bundlers.get(&caller) {
-        Some(Some(block)) => block,
-        Some(None) => {
-            return Err(ContractError::WithdrawBlocked);
-        }
+pub async fn withdraw(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    current_timestamp: u64,
+) -> ActionResult {
+    let bundler = match state.bundlers.get(&caller) {
+        Some(bundler) => bundler,
         None => {
             return Err(ContractError::InvalidBundler(caller));
         }
     };
 
-    if **withdraw_allowed_in_block > Block::height() as u128 {
-        log(&format!(
-            "withdraw blocked, withdraw allowed in block {}, current block height {}",
-            **withdraw_allowed_in_block,
-            Block::height()
-        ));
-        return Err(ContractError::WithdrawBlocked);
+    if let Some(block) = bundler.jailed_until {
+        return Err(ContractError::BundlerJailed(block));
     }
 
-    let result = SmartWeave::write(
-        &state.token.to_string(),
-        JsValue::from_serde(&Input {
-            function: "transfer".to_string(),
-            to: caller.clone(),
-            amount: state.stake,
-        })
-        .unwrap(),
-    )
-    .await;
-
-    let result: Result = result.into_serde().unwrap();
-
-    if result.result_type != "ok" {
-        return Err(ContractError::TransferFailed);
+    if !bundler.can_withdraw(current_block_height, current_timestamp) {
+        return Err(ContractError::WithdrawBlocked);
     }
 
+    // Bundler gets back what it has staked, not the current minimum stake,
+    // together with the tranches still unbonding
+    let stake = bundler
+        .unbonding
+        .values()
+        .fold(bundler.stake, |total, amount| total + *amount);
+
+    token::transfer(bundler.token(&state.token), caller.clone(), stake).await?;
+
+    settle_all(&mut state, &caller, current_block_height);
     state.bundlers.remove(&caller);
 
     Ok(HandlerResult::NewState(state))
 }
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        actions::token::mock::{contract_address, take_transfers},
+        error::ContractError,
+        state::State,
+        testing::{address, bundler, new_state, state},
+    };
+
+    use super::withdraw;
+
+    // Bundler b1 leaving at height 110 with the given stake token, 300 staked
+    // and 50 still unbonding
+    fn leaving_state(stake_token: Option<&str>) -> State {
+        let mut state = state();
+        let mut b1 = bundler(300, 1);
+        b1.stake_token = stake_token.map(address);
+        b1.leaving = Some(BlockHeight(110));
+        b1.unbonding.insert(BlockHeight(105), Amount::from(50));
+        state.bundlers.insert(address("b1"), b1);
+        state
+    }
+
+    #[test]
+    fn stake_is_withdrawn_once_leaving_matures() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(withdraw(leaving_state(None), address("b1"), 109, 11_080));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked));
+        assert!(take_transfers().is_empty());
+
+        let state =
+            new_state(pool.run_until(withdraw(leaving_state(None), address("b1"), 110, 11_200)));
+        assert!(state.bundlers.is_empty());
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                contract_address(),
+                address("b1"),
+                Amount::from(350)
+            )]
+        );
+    }
+
+    #[test]
+    fn stake_is_withdrawn_in_the_stake_token() {
+        let mut pool = LocalPool::new();
+
+        let state = new_state(pool.run_until(withdraw(
+            leaving_state(Some("other")),
+            address("b1"),
+            110,
+            11_200,
+        )));
+        assert!(state.bundlers.is_empty());
+        // Unbonding tranches are held in the same token as the stake
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("other"),
+                contract_address(),
+                address("b1"),
+                Amount::from(350)
+            )]
+        );
+    }
+
+    #[test]
+    fn only_bundlers_can_withdraw() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(withdraw(leaving_state(None), address("b2"), 110, 11_200));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidBundler(address("b2")))
+        );
+    }
+}
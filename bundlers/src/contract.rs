@@ -1,4 +1,4 @@
-use bundlr_contracts_shared::contract_utils::js_imports::Contract;
+use bundlr_contracts_shared::contract_utils::js_imports::{Block, Contract, SmartWeave};
 use bundlr_contracts_shared::Address;
 
 use crate::action::{Action, ActionResult};
@@ -6,33 +6,323 @@ use crate::actions;
 use crate::error::ContractError;
 use crate::state::State;
 
-pub async fn handle(current_state: State, action: Action) -> ActionResult {
+fn caller() -> Result<Address, ContractError> {
+    SmartWeave::caller()
+        .parse::<Address>()
+        .map_err(|err| ContractError::ParseError(err.to_string()))
+}
+
+pub async fn handle(mut current_state: State, action: Action) -> ActionResult {
+    current_state.migrate_legacy_bundlers(Block::height() as u128);
+    let current_state =
+        actions::config::apply_pending_config(current_state, Block::height() as u128);
+
     match action {
         Action::Bundlers => actions::queries::bunders(current_state),
+        Action::Bundler { address } => actions::queries::bundler(current_state, address),
+        Action::ActiveBundlers { start_after, limit } => actions::queries::active_bundlers(
+            current_state,
+            Block::height() as u128,
+            start_after,
+            limit,
+        ),
+        Action::LeavingBundlers { start_after, limit } => actions::queries::leaving_bundlers(
+            current_state,
+            Block::height() as u128,
+            Block::timestamp() as u64,
+            start_after,
+            limit,
+        ),
+        Action::BundlerStatus { address } => actions::queries::bundler_status(
+            current_state,
+            Block::height() as u128,
+            Block::timestamp() as u64,
+            address,
+        ),
+        Action::BundlersByCurrency { currency } => {
+            actions::queries::bundlers_by_currency(current_state, Block::height() as u128, currency)
+        }
         Action::WithdrawDelay => actions::queries::withdraw_delay(current_state),
         Action::Stake => actions::queries::stake(current_state),
         Action::Token => actions::queries::token(current_state),
-        Action::Join => actions::join(current_state).await,
-        Action::Leave => actions::leave(current_state).await,
-        Action::Withdraw => actions::withdraw(current_state).await,
-        Action::SyncSlashed => {
-            // Delete bundler and move all tokens to a treasury address.
-            todo!()
+        Action::Treasury => actions::queries::treasury(current_state),
+        Action::ValidatorsContract => actions::queries::validators_contract(current_state),
+        Action::PendingConfig => actions::config::pending_config(current_state),
+        Action::Join {
+            stake,
+            profile,
+            tier,
+            token,
+        } => {
+            actions::join(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                stake,
+                profile,
+                tier,
+                token,
+            )
+            .await
+        }
+        Action::UpdateProfile { profile } => {
+            actions::update_profile(current_state, caller()?, profile).await
+        }
+        Action::Leave => {
+            actions::leave(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+            )
+            .await
+        }
+        Action::CancelLeave => {
+            actions::cancel_leave(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+            )
+            .await
+        }
+        Action::IncreaseStake { amount } => {
+            actions::increase_stake(current_state, caller()?, Block::height() as u128, amount).await
+        }
+        Action::DecreaseStake { amount } => {
+            actions::decrease_stake(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+                amount,
+            )
+            .await
+        }
+        Action::WithdrawUnbonded { maturity_height } => {
+            actions::withdraw_unbonded(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+                maturity_height,
+            )
+            .await
+        }
+        Action::Withdraw => {
+            actions::withdraw(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+            )
+            .await
+        }
+        Action::SyncSlashed => actions::sync_slashed(current_state, Block::height() as u128).await,
+        Action::Delegations { bundler } => actions::delegation::delegations(current_state, bundler),
+        Action::Delegate { bundler, amount } => {
+            actions::delegation::delegate(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                bundler,
+                amount,
+            )
+            .await
+        }
+        Action::Undelegate { bundler, amount } => {
+            actions::delegation::undelegate(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+                bundler,
+                amount,
+            )
+            .await
+        }
+        Action::WithdrawDelegation { bundler } => {
+            actions::delegation::withdraw_delegation(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                Block::timestamp() as u64,
+                bundler,
+            )
+            .await
+        }
+        Action::PendingRewards { address } => {
+            actions::rewards::pending_rewards(current_state, Block::height() as u128, address)
+        }
+        Action::DepositRewards { amount } => {
+            actions::rewards::deposit_rewards(current_state, caller()?, amount).await
+        }
+        Action::ClaimRewards => {
+            actions::rewards::claim_rewards(current_state, caller()?, Block::height() as u128).await
+        }
+        Action::JailHistory { address } => actions::jail::jail_history(current_state, address),
+        Action::Jail { bundler, reason } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::jail::jail(
+                current_state,
+                owner,
+                caller()?,
+                Block::height() as u128,
+                bundler,
+                reason,
+            )
+            .await
+        }
+        Action::Unjail => {
+            actions::jail::unjail(current_state, caller()?, Block::height() as u128).await
+        }
+        Action::Tiers => actions::tiers::tiers(current_state),
+        Action::SetTier {
+            name,
+            capacity,
+            stake,
+        } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::tiers::set_tier(current_state, owner, caller()?, name, capacity, stake).await
+        }
+        Action::RemoveTier { name } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::tiers::remove_tier(current_state, owner, caller()?, name).await
+        }
+        Action::StakeTokens => actions::stake_tokens::stake_tokens(current_state),
+        Action::SetStakeToken { token, weight } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::stake_tokens::set_stake_token(current_state, owner, caller()?, token, weight)
+                .await
+        }
+        Action::RemoveStakeToken { token } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::stake_tokens::remove_stake_token(current_state, owner, caller()?, token).await
+        }
+        Action::ChangeTier { tier } => {
+            actions::tiers::change_tier(current_state, caller()?, tier).await
+        }
+        Action::SlashProposals { bundler } => {
+            actions::validators::slash_proposals(current_state, bundler).await
+        }
+        Action::SetValidatorsContract {
+            validators_contract,
+        } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::validators::set_validators_contract(
+                current_state,
+                owner,
+                caller()?,
+                validators_contract,
+            )
+            .await
+        }
+        Action::ForceRemove {
+            bundler,
+            destination,
+            reason,
+        } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::force_remove::force_remove(
+                current_state,
+                owner,
+                caller()?,
+                Block::height() as u128,
+                bundler,
+                destination,
+                reason,
+            )
+            .await
+        }
+        Action::Liveness { address } => {
+            actions::heartbeat::liveness(current_state, Block::height() as u128, address)
+        }
+        Action::Heartbeat { epoch, stats } => {
+            actions::heartbeat::heartbeat(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                epoch,
+                stats,
+            )
+            .await
         }
         Action::AllowedInteractors => actions::queries::allowed_interactors(current_state),
+        Action::Roles => actions::roles::roles(current_state),
+        Action::GrantRole { role, address } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::roles::grant(current_state, owner, caller()?, role, address).await
+        }
+        Action::RevokeRole { role, address } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::roles::revoke(current_state, owner, caller()?, role, address).await
+        }
         Action::AddAllowedInteractor { interactor } => {
             let owner = Contract::owner()
                 .parse::<Address>()
                 .map_err(|err| ContractError::ParseError(err.to_string()))?;
 
-            actions::interactors::add(current_state, owner, interactor).await
+            actions::interactors::add(current_state, owner, caller()?, interactor).await
+        }
+        Action::UpdateConfig {
+            withdraw_delay,
+            stake,
+            treasury,
+            heartbeat_window,
+            max_missed_heartbeats,
+            config_timelock,
+        } => {
+            let owner = Contract::owner()
+                .parse::<Address>()
+                .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+            actions::config::update_config(
+                current_state,
+                owner,
+                caller()?,
+                Block::height() as u128,
+                withdraw_delay,
+                stake,
+                treasury,
+                heartbeat_window,
+                max_missed_heartbeats,
+                config_timelock,
+            )
+            .await
         }
         Action::RemoveAllowedInteractor { interactor } => {
             let owner = Contract::owner()
                 .parse::<Address>()
                 .map_err(|err| ContractError::ParseError(err.to_string()))?;
 
-            actions::interactors::remove(current_state, owner, interactor).await
+            actions::interactors::remove(current_state, owner, caller()?, interactor).await
         }
     }
 }
@@ -1,16 +1,45 @@
-use bundlr_contracts_shared::{Address, BlockHeight};
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
 use serde::Serialize;
 
-#[derive(Serialize)]
+use crate::state::Role;
+
+#[derive(Debug, PartialEq, Serialize)]
 pub enum ContractError {
     AlreadyJoined(Address),
     AlreadyLeaving(Address, BlockHeight),
+    AlreadyWithdrawable(BlockHeight),
+    AlreadyHasRole(Address, Role),
     AlreadyInteractor(Address),
+    AlreadyJailed(Address),
+    AmountMustBeHigherThanZero,
+    BundlerJailed(BlockHeight),
     Forbidden,
+    InsufficientStake(Amount),
+    InsufficientDelegation(Amount),
     InvalidBundler(Address),
+    InvalidDelegation(Address),
+    InvalidEpoch(u64),
     InvalidInteractor(Address),
+    InvalidProfile(String),
+    InvalidStakeToken(Address),
+    InvalidTier(String),
+    InvalidValidatorsContract(Address),
+    InvalidWithdrawDelay,
+    MissingRole(Address, Role),
+    NotJailed(Address),
+    NotLeaving(Address),
+    MissingReason,
+    NoActiveStake,
+    NoRewards,
+    NothingToSlash,
+    NothingToUpdate,
     ParseError(String),
     RuntimeError(String),
+    TierInUse(String),
+    TrancheNotFound(BlockHeight),
+    TrancheNotMatured(BlockHeight),
     TransferFailed,
+    TreasuryNotSet,
+    ValidatorsContractNotSet,
     WithdrawBlocked,
 }
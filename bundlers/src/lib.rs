@@ -4,3 +4,5 @@ mod contract;
 pub mod contract_utils;
 mod error;
 mod state;
+#[cfg(test)]
+mod testing;
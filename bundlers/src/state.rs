@@ -1,15 +1,589 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+use bundlr_contracts_shared::{u128_utils, Address, Amount, BlockHeight, TransactionId};
+
+use crate::error::ContractError;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    // public URL of the bundler node
+    pub url: String,
+    // currencies the bundler accepts payments in, stored lowercase
+    pub currencies: HashSet<String>,
+    // public key the bundler signs receipts with
+    pub public_key: String,
+    // maximum size of a data item the bundler accepts, in bytes
+    pub max_data_size: u64,
+    pub contact: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Tier {
+    // data throughput the bundler commits to, in bytes per day
+    pub capacity: u64,
+    // stake required from bundlers in the tier
+    pub stake: Amount,
+}
+
+// Stake token weights are in basis points of the primary token
+pub const STAKE_WEIGHT_PRECISION: u32 = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeToken {
+    // value of the token in the primary token, in basis points
+    pub weight: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatStats {
+    // bytes uploaded through the bundler since the previous heartbeat
+    pub uploaded_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Liveness {
+    // block height of the last heartbeat
+    pub last_seen: Option<BlockHeight>,
+    pub last_epoch: Option<u64>,
+    pub heartbeats: u64,
+    // total bytes uploaded as reported by the heartbeats
+    #[serde(with = "u128_utils")]
+    pub uploaded_bytes: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundler {
+    // amount of tokens the bundler has staked
+    pub stake: Amount,
+    // token the stake was deposited in, the primary token when None
+    #[serde(default)]
+    pub stake_token: Option<Address>,
+    // weight of the stake token when the bundler joined, in basis points
+    #[serde(default = "default_stake_weight")]
+    pub stake_weight: u32,
+    // block height when the bundler joined
+    pub joined: BlockHeight,
+    // block height after which the bundler can withdraw, set when leaving,
+    // estimated from the average block time when the withdraw delay is a duration
+    pub leaving: Option<BlockHeight>,
+    // timestamp after which the bundler can withdraw, set when leaving while
+    // the withdraw delay is a duration, takes precedence over the height
+    #[serde(default)]
+    pub leaving_until: Option<u64>,
+    // unbonded stake, keyed by the block height from which it can be withdrawn
+    #[serde(default)]
+    pub unbonding: BTreeMap<BlockHeight, Amount>,
+    // timestamps after which the tranches unbonded while the withdraw delay is
+    // a duration can be withdrawn, keyed like the tranches
+    #[serde(default)]
+    pub unbonding_until: BTreeMap<BlockHeight, u64>,
+    // block height after which a jailed bundler can unjail
+    #[serde(default)]
+    pub jailed_until: Option<BlockHeight>,
+    #[serde(default)]
+    pub liveness: Liveness,
+    #[serde(default)]
+    pub profile: Option<Profile>,
+    // capacity tier the bundler has committed to
+    #[serde(default)]
+    pub tier: Option<String>,
+    // total amount delegated to the bundler, excluding the unbonding part
+    #[serde(default)]
+    pub delegated: Amount,
+    // value of the global reward index when the bundler rewards were last settled
+    #[serde(default, with = "u128_utils")]
+    pub reward_index: u128,
+    // cumulative rewards per delegated token earned by the bundler delegators
+    #[serde(default, with = "u128_utils")]
+    pub delegator_reward_index: u128,
+    // read from the state written before the bundler details were kept, the
+    // stake is filled in by State::migrate_legacy_bundlers
+    #[serde(skip)]
+    pub legacy: bool,
+}
+
+// Before the bundler details were kept, each bundler was stored only as the
+// height it could withdraw from, or null while staked, and all of them had
+// staked the global stake
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BundlerEntry {
+    Bundler(Box<Bundler>),
+    Legacy(Option<BlockHeight>),
+}
+
+fn deserialize_bundlers<'de, D>(deserializer: D) -> Result<HashMap<Address, Bundler>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries = HashMap::<Address, BundlerEntry>::deserialize(deserializer)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|(address, entry)| {
+            let bundler = match entry {
+                BundlerEntry::Bundler(bundler) => *bundler,
+                BundlerEntry::Legacy(leaving) => Bundler {
+                    stake: Amount::ZERO,
+                    stake_token: None,
+                    stake_weight: STAKE_WEIGHT_PRECISION,
+                    joined: BlockHeight(0),
+                    leaving,
+                    leaving_until: None,
+                    unbonding: BTreeMap::new(),
+                    unbonding_until: BTreeMap::new(),
+                    jailed_until: None,
+                    liveness: Liveness::default(),
+                    profile: None,
+                    tier: None,
+                    delegated: Amount::ZERO,
+                    reward_index: 0,
+                    delegator_reward_index: 0,
+                    legacy: true,
+                },
+            };
+            (address, bundler)
+        })
+        .collect())
+}
+
+fn default_stake_weight() -> u32 {
+    STAKE_WEIGHT_PRECISION
+}
+
+impl Bundler {
+    // Token the stake, including the unbonding tranches, is held in
+    pub fn token<'a>(&'a self, primary: &'a Address) -> &'a Address {
+        self.stake_token.as_ref().unwrap_or(primary)
+    }
+
+    // Value of the given amount of the stake token in the primary token
+    pub fn weighted(&self, amount: Amount) -> Amount {
+        let weight = self.stake_weight as u128;
+        let precision = STAKE_WEIGHT_PRECISION as u128;
+        Amount::from(*amount / precision * weight + *amount % precision * weight / precision)
+    }
+
+    // Stake counted towards the required stake and the rewards
+    pub fn weighted_stake(&self) -> Amount {
+        self.weighted(self.stake)
+    }
+
+    // Active bundlers are the ones earning rewards and listed as active
+    pub fn is_active(&self) -> bool {
+        self.leaving.is_none() && self.jailed_until.is_none()
+    }
+
+    // Number of whole heartbeat windows passed since the last heartbeat or joining
+    pub fn missed_heartbeat_windows(&self, height: u128, heartbeat_window: u64) -> u64 {
+        if heartbeat_window == 0 {
+            return 0;
+        }
+        let last_seen = self.liveness.last_seen.unwrap_or(self.joined);
+        (height.saturating_sub(*last_seen) / heartbeat_window as u128) as u64
+    }
+
+    // Whether a leaving bundler has waited out the withdraw delay
+    pub fn can_withdraw(&self, height: u128, timestamp: u64) -> bool {
+        match self.leaving {
+            Some(leaving) => is_matured(leaving, self.leaving_until, height, timestamp),
+            None => false,
+        }
+    }
+
+    // Timestamp after which a leaving bundler can withdraw, estimated from the
+    // average block time when the withdraw delay is in blocks
+    pub fn unlock_time(
+        &self,
+        height: u128,
+        timestamp: u64,
+        average_block_time: u64,
+    ) -> Option<u64> {
+        match (self.leaving, self.leaving_until) {
+            (Some(_), Some(leaving_until)) => Some(leaving_until),
+            (Some(leaving), None) => {
+                Some(timestamp + leaving.saturating_sub(height) as u64 * average_block_time)
+            }
+            (None, _) => None,
+        }
+    }
+}
+
+// Whether the withdraw delay ending at the given height, or at the given
+// timestamp when the delay is a duration, has passed
+pub fn is_matured(
+    matures_at: BlockHeight,
+    matures_until: Option<u64>,
+    height: u128,
+    timestamp: u64,
+) -> bool {
+    match matures_until {
+        Some(matures_until) => matures_until <= timestamp,
+        None => *matures_at <= height,
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JailRecord {
+    pub jailed_at: BlockHeight,
+    pub jailed_until: BlockHeight,
+    pub reason: String,
+    pub unjailed_at: Option<BlockHeight>,
+}
+
+// Where the stake of a force removed bundler is sent
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Destination {
+    Bundler,
+    Treasury,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovalRecord {
+    pub bundler: Address,
+    pub destination: Destination,
+    // address the stake was sent to
+    pub recipient: Address,
+    pub stake: Amount,
+    pub reason: String,
+    pub removed_at: BlockHeight,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    // can manage the allowed interactors and schedule configuration changes
+    Admin,
+    // can manage the allowed interactors
+    AllowlistManager,
+    // can join as a bundler, holders are the allowed interactors
+    BundlerEligible,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Delegation {
+    // amount backing the bundler
+    pub amount: Amount,
+    // amount undelegated but still locked, and slashable, until withdrawable_at
+    pub unbonding: Amount,
+    pub withdrawable_at: Option<BlockHeight>,
+    // timestamp after which the unbonding amount can be withdrawn, set when
+    // undelegating while the withdraw delay is a duration, takes precedence
+    // over the height
+    #[serde(default)]
+    pub withdrawable_until: Option<u64>,
+    // value of the bundler delegator reward index when the rewards were last settled
+    #[serde(default, with = "u128_utils")]
+    pub reward_index: u128,
+}
+
+// Withdraw delay is either a number of blocks, kept as a plain number for
+// compatibility with the older state, or a duration in seconds
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum WithdrawDelay {
+    Blocks(u64),
+    Duration { seconds: u64 },
+}
+
+impl Default for WithdrawDelay {
+    fn default() -> Self {
+        WithdrawDelay::Blocks(0)
+    }
+}
+
+// Configuration change scheduled by the owner, fields left as None are unchanged
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingConfig {
+    pub withdraw_delay: Option<WithdrawDelay>,
+    pub stake: Option<Amount>,
+    pub treasury: Option<Address>,
+    pub heartbeat_window: Option<u64>,
+    pub max_missed_heartbeats: Option<u64>,
+    #[serde(default)]
+    pub config_timelock: Option<u64>,
+    // block height from which the change is applied
+    pub effective_height: BlockHeight,
+}
+
+// roughly a month worth of blocks
+fn default_config_timelock() -> u64 {
+    21600
+}
+
+// roughly a day worth of blocks
+fn default_jail_cooldown() -> u64 {
+    720
+}
+
+fn default_average_block_time() -> u64 {
+    120
+}
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct State {
     pub token: Address,
-    pub bundlers: HashMap<Address, Option<BlockHeight>>,
-    pub withdraw_delay: u16,
+    #[serde(deserialize_with = "deserialize_bundlers")]
+    pub bundlers: HashMap<Address, Bundler>,
+    // delegated stake, keyed by bundler and then by delegator
+    #[serde(default)]
+    pub delegations: HashMap<Address, HashMap<Address, Delegation>>,
+    pub withdraw_delay: WithdrawDelay,
+    // average number of seconds between blocks, used to convert between
+    // heights and timestamps where only an estimate is needed
+    #[serde(default = "default_average_block_time")]
+    pub average_block_time: u64,
+    // minimum stake required from each bundler, in the primary token
     pub stake: Amount,
+    // tokens other than the primary token accepted as stake
+    #[serde(default)]
+    pub stake_tokens: HashMap<Address, StakeToken>,
+    // capacity tiers bundlers can commit to, keyed by the tier name
+    #[serde(default)]
+    pub tiers: HashMap<String, Tier>,
+    // addresses holding the bundler eligible role
     pub allowed_interactors: HashSet<Address>,
+    // roles granted by the owner, except bundler eligibility which is kept in allowed_interactors
+    #[serde(default)]
+    pub roles: HashMap<Address, HashSet<Role>>,
+    // validators contract whose concluded slash proposals are applied to the bundlers
+    #[serde(default)]
+    pub validators_contract: Option<Address>,
+    // address receiving the stake of slashed bundlers
+    #[serde(default)]
+    pub treasury: Option<Address>,
+    // slash proposals (ID of the tx the proposal refers to) already applied by SyncSlashed
+    #[serde(default)]
+    pub applied_slash_proposals: HashSet<TransactionId>,
+    #[serde(default)]
+    pub pending_config: Option<PendingConfig>,
+    // number of blocks a configuration change waits before it is applied
+    #[serde(default = "default_config_timelock")]
+    pub config_timelock: u64,
+    // length of the window in blocks within which bundlers are expected to
+    // send a heartbeat, zero if heartbeats are not required
+    #[serde(default)]
+    pub heartbeat_window: u64,
+    // bundlers missing this many heartbeat windows in a row are inactive,
+    // zero if bundlers never become inactive
+    #[serde(default)]
+    pub max_missed_heartbeats: u64,
+    // number of blocks a jailed bundler has to wait before it can unjail
+    #[serde(default = "default_jail_cooldown")]
+    pub jail_cooldown: u64,
+    // jail records per bundler, kept after the bundler withdraws
+    #[serde(default)]
+    pub jail_history: HashMap<Address, Vec<JailRecord>>,
+    // force removals by the owner, in the order they happened
+    #[serde(default)]
+    pub removals: Vec<RemovalRecord>,
+    // cumulative rewards per staked token, scaled by REWARD_INDEX_PRECISION
+    #[serde(default, with = "u128_utils")]
+    pub reward_index: u128,
+    // weighted stake of active bundlers together with the amount delegated to them
+    #[serde(default)]
+    pub active_stake: Amount,
+    // settled rewards which can be claimed
+    #[serde(default)]
+    pub rewards: HashMap<Address, Amount>,
+}
+
+impl State {
+    // Completes the bundlers read from the state written before the bundler
+    // details were kept, they get the global stake and join at the given height
+    pub fn migrate_legacy_bundlers(&mut self, height: u128) {
+        let stake = self.stake;
+        let reward_index = self.reward_index;
+        let mut migrated_active_stake = Amount::ZERO;
+
+        for bundler in self.bundlers.values_mut().filter(|bundler| bundler.legacy) {
+            bundler.stake = stake;
+            bundler.joined = BlockHeight(height);
+            bundler.reward_index = reward_index;
+            bundler.legacy = false;
+            if bundler.is_active() {
+                migrated_active_stake += stake;
+            }
+        }
+
+        self.active_stake += migrated_active_stake;
+    }
+
+    // Stake required from a bundler in the given tier, never lower than the
+    // minimum stake
+    pub fn required_stake(&self, tier: Option<&str>) -> Result<Amount, ContractError> {
+        let tier_stake = match tier {
+            Some(name) => {
+                self.tiers
+                    .get(name)
+                    .ok_or_else(|| ContractError::InvalidTier(name.to_string()))?
+                    .stake
+            }
+            None => Amount::ZERO,
+        };
+
+        Ok(if tier_stake > self.stake {
+            tier_stake
+        } else {
+            self.stake
+        })
+    }
+
+    // Weight of the given stake token, the primary token is used when None
+    pub fn stake_weight(&self, token: Option<&Address>) -> Result<u32, ContractError> {
+        match token {
+            None => Ok(STAKE_WEIGHT_PRECISION),
+            Some(token) if *token == self.token => Ok(STAKE_WEIGHT_PRECISION),
+            Some(token) => self
+                .stake_tokens
+                .get(token)
+                .map(|stake_token| stake_token.weight)
+                .ok_or_else(|| ContractError::InvalidStakeToken(token.clone())),
+        }
+    }
+
+    // Active bundler which has missed too many heartbeat windows
+    pub fn is_inactive(&self, bundler: &Bundler, height: u128) -> bool {
+        self.max_missed_heartbeats != 0
+            && bundler.missed_heartbeat_windows(height, self.heartbeat_window)
+                >= self.max_missed_heartbeats
+    }
+
+    // Height, and timestamp when the withdraw delay is a duration, at which a
+    // withdraw delay starting now ends
+    pub fn withdraw_maturity(&self, height: u128, timestamp: u64) -> (BlockHeight, Option<u64>) {
+        let matures_until = match self.withdraw_delay {
+            WithdrawDelay::Blocks(_) => None,
+            WithdrawDelay::Duration { seconds } => Some(timestamp + seconds),
+        };
+
+        (
+            BlockHeight(height + self.withdraw_delay_blocks()),
+            matures_until,
+        )
+    }
+
+    // Withdraw delay in blocks, estimated from the average block time when the
+    // delay is a duration
+    pub fn withdraw_delay_blocks(&self) -> u128 {
+        match self.withdraw_delay {
+            WithdrawDelay::Blocks(blocks) => blocks as u128,
+            WithdrawDelay::Duration { seconds } => {
+                seconds.div_ceil(self.average_block_time.max(1)) as u128
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+
+    use crate::testing::{address, bundler, join, state};
+
+    use super::{State, WithdrawDelay};
+
+    fn legacy_state() -> State {
+        serde_json::from_str(include_str!("../tests/data/bundlers-legacy.json")).unwrap()
+    }
+
+    #[test]
+    fn legacy_state_is_parsed() {
+        let state = legacy_state();
+
+        assert_eq!(state.withdraw_delay, WithdrawDelay::Blocks(21600));
+        assert_eq!(state.stake, Amount::from(100));
+
+        let staked = &state.bundlers[&address("bundler1")];
+        assert!(staked.legacy);
+        assert_eq!(staked.leaving, None);
+
+        let leaving = &state.bundlers[&address("bundler2")];
+        assert!(leaving.legacy);
+        assert_eq!(leaving.leaving, Some(BlockHeight(2100)));
+    }
+
+    #[test]
+    fn legacy_bundlers_get_the_global_stake() {
+        let mut state = legacy_state();
+        state.migrate_legacy_bundlers(1000);
+
+        for bundler in state.bundlers.values() {
+            assert!(!bundler.legacy);
+            assert_eq!(bundler.stake, Amount::from(100));
+            assert_eq!(bundler.joined, BlockHeight(1000));
+        }
+        // Only the bundler which is not leaving earns rewards
+        assert_eq!(state.active_stake, Amount::from(100));
+
+        // Migrated state is written in the current format and read back as is
+        let mut state: State =
+            serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert!(state.bundlers.values().all(|bundler| !bundler.legacy));
+        state.migrate_legacy_bundlers(2000);
+        assert_eq!(state.active_stake, Amount::from(100));
+        assert_eq!(
+            state.bundlers[&address("bundler1")].joined,
+            BlockHeight(1000)
+        );
+    }
+
+    #[test]
+    fn current_bundlers_are_not_migrated() {
+        let mut state = state();
+        join(&mut state, "b1", bundler(500, 1));
+        state.migrate_legacy_bundlers(1000);
+
+        assert_eq!(state.bundlers[&address("b1")].stake, Amount::from(500));
+        assert_eq!(state.active_stake, Amount::from(500));
+    }
+
+    #[test]
+    fn missed_heartbeat_windows_count_whole_windows() {
+        let mut bundler = bundler(100, 100);
+
+        // Heartbeats are not required
+        assert_eq!(bundler.missed_heartbeat_windows(1000, 0), 0);
+
+        // Counted from joining until the first heartbeat
+        assert_eq!(bundler.missed_heartbeat_windows(100, 10), 0);
+        assert_eq!(bundler.missed_heartbeat_windows(109, 10), 0);
+        assert_eq!(bundler.missed_heartbeat_windows(110, 10), 1);
+        assert_eq!(bundler.missed_heartbeat_windows(135, 10), 3);
+
+        bundler.liveness.last_seen = Some(BlockHeight(130));
+        assert_eq!(bundler.missed_heartbeat_windows(135, 10), 0);
+        assert_eq!(bundler.missed_heartbeat_windows(150, 10), 2);
+        // Height before the last heartbeat does not underflow
+        assert_eq!(bundler.missed_heartbeat_windows(120, 10), 0);
+    }
+
+    #[test]
+    fn bundler_is_inactive_after_missing_enough_windows() {
+        let mut state = state();
+        state.heartbeat_window = 10;
+        state.max_missed_heartbeats = 3;
+        let bundler = bundler(100, 100);
+
+        assert!(!state.is_inactive(&bundler, 129));
+        assert!(state.is_inactive(&bundler, 130));
+
+        // Bundlers never become inactive without the limit
+        state.max_missed_heartbeats = 0;
+        assert!(!state.is_inactive(&bundler, 1000));
+    }
 }
@@ -0,0 +1,63 @@
+// Fixtures shared by the unit tests of the actions
+
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+
+use crate::{
+    action::ActionResult,
+    contract_utils::handler_result::HandlerResult,
+    state::{Bundler, State, WithdrawDelay},
+};
+
+pub fn address(address: &str) -> Address {
+    Address::try_from(address).unwrap()
+}
+
+// Contract with a minimum stake of 100, a withdraw delay of 10 blocks and a
+// config timelock of 50 blocks
+pub fn state() -> State {
+    State {
+        token: address("token"),
+        withdraw_delay: WithdrawDelay::Blocks(10),
+        config_timelock: 50,
+        average_block_time: 120,
+        stake: Amount::from(100),
+        jail_cooldown: 720,
+        ..Default::default()
+    }
+}
+
+// Active bundler staked in the primary token
+pub fn bundler(stake: u128, joined: u128) -> Bundler {
+    Bundler {
+        stake: Amount::from(stake),
+        stake_token: None,
+        stake_weight: 10_000,
+        joined: BlockHeight(joined),
+        leaving: None,
+        leaving_until: None,
+        unbonding: Default::default(),
+        unbonding_until: Default::default(),
+        jailed_until: None,
+        liveness: Default::default(),
+        profile: None,
+        tier: None,
+        delegated: Amount::ZERO,
+        reward_index: 0,
+        delegator_reward_index: 0,
+        legacy: false,
+    }
+}
+
+// Adds an active bundler to the state, keeping the active stake in sync
+pub fn join(state: &mut State, bundler_address: &str, bundler: Bundler) {
+    state.active_stake += bundler.weighted_stake() + bundler.delegated;
+    state.bundlers.insert(address(bundler_address), bundler);
+}
+
+pub fn new_state(result: ActionResult) -> State {
+    match result {
+        Ok(HandlerResult::NewState(state)) => state,
+        Ok(HandlerResult::QueryResponse(_)) => panic!("expected a new state, got a query response"),
+        Err(err) => panic!("expected a new state, got {:?}", err),
+    }
+}
@@ -166,7 +166,28 @@ describe("Bundlers Contract", () => {
     );
   });
 
-  it("allowed interactor has right to add other interactors", async () => {
+  it("allowed interactor has no right to add other interactors", async () => {
+    await connections[1].bundlers.addAllowedInteractor(accounts[2].address);
+    await mineBlock(arweave);
+
+    expect(await connections[0].bundlers.allowedInteractors()).not.toContain(
+      accounts[2].address
+    );
+  });
+
+  it("contract owner can grant allowlist manager role", async () => {
+    await connections[0].bundlers.grantRole(
+      "allowlistManager",
+      accounts[1].address
+    );
+    await mineBlock(arweave);
+
+    expect((await connections[0].bundlers.roles())[accounts[1].address]).toEqual(
+      expect.arrayContaining(["allowlistManager", "bundlerEligible"])
+    );
+  });
+
+  it("allowlist manager has right to add other interactors", async () => {
     expect(await connections[0].bundlers.allowedInteractors()).not.toContain(
       accounts[2].address
     );
@@ -186,7 +207,7 @@ describe("Bundlers Contract", () => {
     );
   });
 
-  it("allowed interactor has right to remove other interactors", async () => {
+  it("allowlist manager has right to remove other interactors", async () => {
     expect(await connections[0].bundlers.allowedInteractors()).toContain(
       accounts[3].address
     );
@@ -277,7 +298,7 @@ describe("Bundlers Contract", () => {
       )
     );
 
-    let withdrawDelay = await connections[1].bundlers.withdrawDelay();
+    let withdrawDelay = (await connections[1].bundlers.withdrawDelay()) as number;
 
     await connections[1].bundlers.leave();
     await mineBlock(arweave);
@@ -9,27 +9,214 @@ import {
   Warp,
 } from "warp-contracts";
 
+export type Profile = {
+  url: string;
+  currencies: string[];
+  publicKey: string;
+  maxDataSize: number;
+  contact: string | null;
+};
+
+export type SlashProposal = {
+  id: string;
+  proposedBy: string;
+  proposedAt: string;
+  finalVote: "for" | "against" | null;
+  applied: boolean;
+};
+
+export type StakeToken = {
+  weight: number;
+};
+
+export type Tier = {
+  capacity: number;
+  stake: string;
+};
+
+export type Liveness = {
+  lastSeen: string | null;
+  lastEpoch: number | null;
+  heartbeats: number;
+  uploadedBytes: string;
+};
+
+export type Bundler = {
+  stake: string;
+  stakeToken: string | null;
+  stakeWeight: number;
+  joined: string;
+  leaving: string | null;
+  leavingUntil: number | null;
+  unbonding: { [key: string]: string };
+  unbondingUntil: { [key: string]: number };
+  jailedUntil: string | null;
+  liveness: Liveness;
+  profile: Profile | null;
+  tier: string | null;
+  delegated: string;
+  rewardIndex: string;
+  delegatorRewardIndex: string;
+};
+
+export type LeavingBundler = {
+  address: string;
+  withdrawHeight: string;
+  blocksRemaining: string;
+  unlockTime: number;
+};
+
+export type BundlerStatus =
+  | "active"
+  | { leaving: { height: string; unlockTime: number } }
+  | { jailed: string }
+  | "inactive"
+  | "withdrawable"
+  | "unknown";
+
+export type JailRecord = {
+  jailedAt: string;
+  jailedUntil: string;
+  reason: string;
+  unjailedAt: string | null;
+};
+
+export type Destination = "bundler" | "treasury";
+
+export type RemovalRecord = {
+  bundler: string;
+  destination: Destination;
+  recipient: string;
+  stake: string;
+  reason: string;
+  removedAt: string;
+};
+
+export type Page = { startAfter?: string; limit?: number };
+
+export type Delegation = {
+  amount: string;
+  unbonding: string;
+  withdrawableAt: string | null;
+  withdrawableUntil: number | null;
+  rewardIndex: string;
+};
+
+export type Role = "admin" | "allowlistManager" | "bundlerEligible";
+
+// Number of blocks, or a duration in seconds
+export type WithdrawDelay = number | { seconds: number };
+
+export type PendingConfig = {
+  withdrawDelay: WithdrawDelay | null;
+  stake: string | null;
+  treasury: string | null;
+  heartbeatWindow: number | null;
+  maxMissedHeartbeats: number | null;
+  configTimelock: number | null;
+  effectiveHeight: string;
+};
+
 export type State = {
-  bundlers: { [key: string]: string | null };
+  bundlers: { [key: string]: Bundler };
+  delegations: { [key: string]: { [key: string]: Delegation } };
   allowedInteractors: string[];
+  roles: { [key: string]: Role[] };
   token: string;
   stake: string;
-  withdrawDelay: number;
+  stakeTokens: { [key: string]: StakeToken };
+  tiers: { [key: string]: Tier };
+  withdrawDelay: WithdrawDelay;
+  averageBlockTime: number;
+  validatorsContract: string | null;
+  treasury: string | null;
+  appliedSlashProposals: string[];
+  pendingConfig: PendingConfig | null;
+  configTimelock: number;
+  heartbeatWindow: number;
+  maxMissedHeartbeats: number;
+  jailCooldown: number;
+  jailHistory: { [key: string]: JailRecord[] };
+  removals: RemovalRecord[];
+  rewardIndex: string;
+  activeStake: string;
+  rewards: { [key: string]: string };
 };
 
 export interface BundlersContract extends Contract<State> {
   currentState(): Promise<State>;
   bundlers(): Promise<{ [key: string]: string }>;
+  bundler(address: string): Promise<Bundler>;
+  activeBundlers(page?: Page): Promise<string[]>;
+  leavingBundlers(page?: Page): Promise<LeavingBundler[]>;
+  bundlerStatus(address: string): Promise<BundlerStatus>;
+  bundlersByCurrency(currency: string): Promise<{ [key: string]: Profile }>;
   allowedInteractors(): Promise<Set<string>>;
-  withdrawDelay(): Promise<number>;
+  roles(): Promise<{ [key: string]: Role[] }>;
+  delegations(bundler: string): Promise<{ [key: string]: Delegation }>;
+  pendingRewards(address: string): Promise<bigint>;
+  jailHistory(address: string): Promise<JailRecord[]>;
+  liveness(address: string): Promise<{
+    liveness: Liveness;
+    missedWindows: number;
+    inactive: boolean;
+  }>;
+  withdrawDelay(): Promise<WithdrawDelay>;
   stake(): Promise<bigint>;
   token(): Promise<string>;
-  join(): Promise<string>;
+  treasury(): Promise<string | null>;
+  validatorsContract(): Promise<string | null>;
+  slashProposals(
+    bundler: string
+  ): Promise<{ open: SlashProposal[]; concluded: SlashProposal[] }>;
+  setValidatorsContract(validatorsContract: string): Promise<string>;
+  pendingConfig(): Promise<PendingConfig | null>;
+  join(
+    stake?: bigint,
+    profile?: Profile,
+    tier?: string,
+    token?: string
+  ): Promise<string>;
+  updateProfile(profile: Profile): Promise<string>;
+  increaseStake(amount: bigint): Promise<string>;
+  decreaseStake(amount: bigint): Promise<string>;
+  withdrawUnbonded(maturityHeight: bigint): Promise<string>;
   leave(): Promise<string>;
+  cancelLeave(): Promise<string>;
   withdraw(): Promise<string>;
   syncSlash(): Promise<string>;
+  delegate(bundler: string, amount: bigint): Promise<string>;
+  undelegate(bundler: string, amount: bigint): Promise<string>;
+  withdrawDelegation(bundler: string): Promise<string>;
+  depositRewards(amount: bigint): Promise<string>;
+  claimRewards(): Promise<string>;
+  jail(bundler: string, reason: string): Promise<string>;
+  unjail(): Promise<string>;
+  tiers(): Promise<{ [key: string]: Tier }>;
+  setTier(name: string, capacity: number, stake: bigint): Promise<string>;
+  removeTier(name: string): Promise<string>;
+  changeTier(tier: string | null): Promise<string>;
+  stakeTokens(): Promise<{ [key: string]: StakeToken }>;
+  setStakeToken(token: string, weight: number): Promise<string>;
+  removeStakeToken(token: string): Promise<string>;
+  forceRemove(
+    bundler: string,
+    destination: Destination,
+    reason: string
+  ): Promise<string>;
+  heartbeat(epoch: number, stats: { uploadedBytes: number }): Promise<string>;
   addAllowedInteractor(address: string): Promise<string>;
   removeAllowedInteractor(address: string): Promise<string>;
+  grantRole(role: Role, address: string): Promise<string>;
+  revokeRole(role: Role, address: string): Promise<string>;
+  updateConfig(config: {
+    withdrawDelay?: WithdrawDelay;
+    stake?: bigint;
+    treasury?: string;
+    heartbeatWindow?: number;
+    maxMissedHeartbeats?: number;
+    configTimelock?: number;
+  }): Promise<string>;
 }
 
 class BundlersContractImpl
@@ -58,6 +245,50 @@ class BundlersContractImpl
     return interactionResult.result as string;
   }
 
+  async treasury() {
+    const interactionResult = await this.viewState({
+      function: "treasury",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as string | null;
+  }
+
+  async validatorsContract() {
+    const interactionResult = await this.viewState({
+      function: "validatorsContract",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as string | null;
+  }
+
+  async pendingConfig() {
+    const interactionResult = await this.viewState({
+      function: "pendingConfig",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as PendingConfig | null;
+  }
+
+  async slashProposals(bundler: string) {
+    const interactionResult = await this.viewState({
+      function: "slashProposals",
+      bundler,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as {
+      open: SlashProposal[];
+      concluded: SlashProposal[];
+    };
+  }
+
   async stake() {
     const interactionResult = await this.viewState({
       function: "stake",
@@ -78,6 +309,61 @@ class BundlersContractImpl
     return interactionResult.result as { [key: string]: string };
   }
 
+  async bundler(address: string) {
+    const interactionResult = await this.viewState({
+      function: "bundler",
+      address,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as Bundler;
+  }
+
+  async activeBundlers(page?: Page) {
+    const interactionResult = await this.viewState({
+      function: "activeBundlers",
+      ...page,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as string[];
+  }
+
+  async leavingBundlers(page?: Page) {
+    const interactionResult = await this.viewState({
+      function: "leavingBundlers",
+      ...page,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as LeavingBundler[];
+  }
+
+  async bundlerStatus(address: string) {
+    const interactionResult = await this.viewState({
+      function: "bundlerStatus",
+      address,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as BundlerStatus;
+  }
+
+  async bundlersByCurrency(currency: string) {
+    const interactionResult = await this.viewState({
+      function: "bundlersByCurrency",
+      currency,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as { [key: string]: Profile };
+  }
+
   async allowedInteractors() {
     const interactionResult = await this.viewState({
       function: "allowedInteractors",
@@ -88,6 +374,84 @@ class BundlersContractImpl
     return interactionResult.result as Set<string>;
   }
 
+  async roles() {
+    const interactionResult = await this.viewState({
+      function: "roles",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as { [key: string]: Role[] };
+  }
+
+  async delegations(bundler: string) {
+    const interactionResult = await this.viewState({
+      function: "delegations",
+      bundler,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as { [key: string]: Delegation };
+  }
+
+  async pendingRewards(address: string) {
+    const interactionResult = await this.viewState({
+      function: "pendingRewards",
+      address,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return BigInt(interactionResult.result as string);
+  }
+
+  async jailHistory(address: string) {
+    const interactionResult = await this.viewState({
+      function: "jailHistory",
+      address,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as JailRecord[];
+  }
+
+  async liveness(address: string) {
+    const interactionResult = await this.viewState({
+      function: "liveness",
+      address,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as {
+      liveness: Liveness;
+      missedWindows: number;
+      inactive: boolean;
+    };
+  }
+
+  async tiers() {
+    const interactionResult = await this.viewState({
+      function: "tiers",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as { [key: string]: Tier };
+  }
+
+  async stakeTokens() {
+    const interactionResult = await this.viewState({
+      function: "stakeTokens",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as { [key: string]: StakeToken };
+  }
+
   async withdrawDelay() {
     const interactionResult = await this.viewState({
       function: "withdrawDelay",
@@ -95,12 +459,49 @@ class BundlersContractImpl
     if (interactionResult.type !== "ok") {
       throw Error(interactionResult.errorMessage);
     }
-    return interactionResult.result as number;
+    return interactionResult.result as WithdrawDelay;
   }
 
-  async join() {
+  async join(
+    stake?: bigint,
+    profile?: Profile,
+    tier?: string,
+    token?: string
+  ) {
     return this.write({
       function: "join",
+      stake: stake?.toString(),
+      profile,
+      tier,
+      token,
+    });
+  }
+
+  async updateProfile(profile: Profile) {
+    return this.write({
+      function: "updateProfile",
+      profile,
+    });
+  }
+
+  async increaseStake(amount: bigint) {
+    return this.write({
+      function: "increaseStake",
+      amount: amount.toString(),
+    });
+  }
+
+  async decreaseStake(amount: bigint) {
+    return this.write({
+      function: "decreaseStake",
+      amount: amount.toString(),
+    });
+  }
+
+  async withdrawUnbonded(maturityHeight: bigint) {
+    return this.write({
+      function: "withdrawUnbonded",
+      maturityHeight: maturityHeight.toString(),
     });
   }
 
@@ -110,6 +511,12 @@ class BundlersContractImpl
     });
   }
 
+  async cancelLeave() {
+    return this.write({
+      function: "cancelLeave",
+    });
+  }
+
   async withdraw() {
     return this.write({
       function: "withdraw",
@@ -118,7 +525,123 @@ class BundlersContractImpl
 
   async syncSlash() {
     return this.write({
-      function: "syncSlash",
+      function: "syncSlashed",
+    });
+  }
+
+  async delegate(bundler: string, amount: bigint) {
+    return this.write({
+      function: "delegate",
+      bundler,
+      amount: amount.toString(),
+    });
+  }
+
+  async undelegate(bundler: string, amount: bigint) {
+    return this.write({
+      function: "undelegate",
+      bundler,
+      amount: amount.toString(),
+    });
+  }
+
+  async withdrawDelegation(bundler: string) {
+    return this.write({
+      function: "withdrawDelegation",
+      bundler,
+    });
+  }
+
+  async depositRewards(amount: bigint) {
+    return this.write({
+      function: "depositRewards",
+      amount: amount.toString(),
+    });
+  }
+
+  async claimRewards() {
+    return this.write({
+      function: "claimRewards",
+    });
+  }
+
+  async jail(bundler: string, reason: string) {
+    return this.write({
+      function: "jail",
+      bundler,
+      reason,
+    });
+  }
+
+  async unjail() {
+    return this.write({
+      function: "unjail",
+    });
+  }
+
+  async heartbeat(epoch: number, stats: { uploadedBytes: number }) {
+    return this.write({
+      function: "heartbeat",
+      epoch,
+      stats,
+    });
+  }
+
+  async setTier(name: string, capacity: number, stake: bigint) {
+    return this.write({
+      function: "setTier",
+      name,
+      capacity,
+      stake: stake.toString(),
+    });
+  }
+
+  async removeTier(name: string) {
+    return this.write({
+      function: "removeTier",
+      name,
+    });
+  }
+
+  async changeTier(tier: string | null) {
+    return this.write({
+      function: "changeTier",
+      tier,
+    });
+  }
+
+  async setStakeToken(token: string, weight: number) {
+    return this.write({
+      function: "setStakeToken",
+      token,
+      weight,
+    });
+  }
+
+  async removeStakeToken(token: string) {
+    return this.write({
+      function: "removeStakeToken",
+      token,
+    });
+  }
+
+  async forceRemove(
+    bundler: string,
+    destination: Destination,
+    reason: string
+  ) {
+    return this.write({
+      function: "forceRemove",
+      bundler,
+      destination,
+      reason,
+    });
+  }
+
+  async setValidatorsContract(validatorsContract: string) {
+    return this.write({
+      function: "setValidatorsContract",
+      validatorsContract,
     });
   }
 
@@ -136,6 +659,41 @@ class BundlersContractImpl
     });
   }
 
+  async grantRole(role: Role, address: string) {
+    return this.write({
+      function: "grantRole",
+      role,
+      address,
+    });
+  }
+
+  async revokeRole(role: Role, address: string) {
+    return this.write({
+      function: "revokeRole",
+      role,
+      address,
+    });
+  }
+
+  async updateConfig(config: {
+    withdrawDelay?: WithdrawDelay;
+    stake?: bigint;
+    treasury?: string;
+    heartbeatWindow?: number;
+    maxMissedHeartbeats?: number;
+    configTimelock?: number;
+  }) {
+    return this.write({
+      function: "updateConfig",
+      withdrawDelay: config.withdrawDelay,
+      stake: config.stake?.toString(),
+      treasury: config.treasury,
+      heartbeatWindow: config.heartbeatWindow,
+      maxMissedHeartbeats: config.maxMissedHeartbeats,
+      configTimelock: config.configTimelock,
+    });
+  }
+
   async write(input: any): Promise<string> {
     const dry = await this.dryWrite(input);
     return this._mainnet
@@ -2,7 +2,7 @@ use std::{convert::Infallible, ops::Deref, str::FromStr};
 
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Address(String);
 
 pub type TransactionId = Address;
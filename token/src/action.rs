@@ -1,9 +1,11 @@
-use bundlr_contracts_shared::{Address, Amount};
+use std::collections::HashSet;
+
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
 use serde::{Deserialize, Serialize};
 
 use crate::contract_utils::handler_result::HandlerResult;
 use crate::error::ContractError;
-use crate::state::State;
+use crate::state::{Escrow, FeeConfig, State};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "function")]
@@ -12,6 +14,21 @@ pub enum Action {
     Symbol,
     Decimals,
     TotalSupply,
+    Info,
+    FeeConfig,
+    #[serde(rename_all = "camelCase")]
+    SetFeeConfig {
+        fee_bps: u16,
+        treasury: String,
+        exempt: HashSet<String>,
+    },
+    UpdateMetadata {
+        name: Option<String>,
+        ticker: Option<String>,
+        logo: Option<String>,
+        description: Option<String>,
+        decimals: Option<u8>,
+    },
     BalanceOf {
         target: String,
     },
@@ -23,6 +40,15 @@ pub enum Action {
         spender: String,
         amount: String,
     },
+    SetSpendingLimit {
+        spender: String,
+        amount: String,
+        period: String,
+    },
+    SpendingLimit {
+        owner: String,
+        spender: String,
+    },
     Burn {
         amount: String,
     },
@@ -39,6 +65,36 @@ pub enum Action {
         to: String,
         amount: String,
     },
+    #[serde(rename_all = "camelCase")]
+    RelayedTransfer {
+        from: String,
+        to: String,
+        amount: String,
+        nonce: u64,
+        deadline: String,
+        public_key: Option<String>,
+        signature: String,
+    },
+    Nonce {
+        address: String,
+    },
+    Escrow {
+        id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    CreateEscrow {
+        to: String,
+        amount: String,
+        hashlock: String,
+        expiry_height: String,
+    },
+    Claim {
+        id: String,
+        preimage: String,
+    },
+    Refund {
+        id: String,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -55,8 +111,32 @@ pub enum QueryResponseMsg {
         ticker: String,
         target: Address,
     },
+    #[serde(rename_all = "camelCase")]
+    SpendingLimit {
+        limit: Amount,
+        remaining: Amount,
+        period_ends: BlockHeight,
+        owner: Address,
+        spender: Address,
+    },
     Decimals(u8),
+    Escrow(Escrow),
+    FeeConfig(Option<FeeConfig>),
+    #[serde(rename_all = "camelCase")]
+    Info {
+        ticker: String,
+        name: Option<String>,
+        logo: Option<String>,
+        description: Option<String>,
+        decimals: u8,
+        total_supply: Amount,
+        owner: Address,
+    },
     Name(Option<String>),
+    Nonce {
+        nonce: u64,
+        address: Address,
+    },
     Symbol(String),
     TotalSupply(Amount),
 }
@@ -1,18 +1,19 @@
 use std::collections::hash_map::Entry;
 
-use bundlr_contracts_shared::{contract_utils::js_imports::SmartWeave, Address, Amount};
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
 
 use crate::action::{ActionResult, QueryResponseMsg};
 use crate::contract_utils::handler_result::HandlerResult;
 use crate::contract_utils::js_imports::log;
 use crate::error::ContractError;
-use crate::state::State;
-
-pub fn approve(mut state: State, spender: Address, amount: Amount) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+use crate::state::{SpendingLimit, State};
 
+pub fn approve(
+    mut state: State,
+    caller: Address,
+    spender: Address,
+    amount: Amount,
+) -> ActionResult {
     // Checking if caller has enough funds
     let caller_balance = *state.balances.get(&caller).unwrap_or(&Amount::ZERO);
     if caller_balance < amount {
@@ -21,6 +22,9 @@ pub fn approve(mut state: State, spender: Address, amount: Amount) -> ActionResu
 
     match amount {
         Amount::ZERO => {
+            // Spending limit of a removed allowance has nothing left to limit
+            remove_spending_limit(&mut state, caller.clone(), &spender);
+
             if let Entry::Occupied(mut caller_allowances) = state.allowances.entry(caller) {
                 if let Entry::Occupied(spender_allowance) =
                     caller_allowances.get_mut().entry(spender)
@@ -70,8 +74,115 @@ pub fn allowance(state: State, owner: Address, spender: Address) -> ActionResult
     }))
 }
 
+pub fn set_spending_limit(
+    mut state: State,
+    caller: Address,
+    current_block_height: u128,
+    spender: Address,
+    amount: Amount,
+    period: u128,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        // Zero amount removes the limit
+        remove_spending_limit(&mut state, caller, &spender);
+        return Ok(HandlerResult::NewState(state));
+    }
+
+    if period == 0 {
+        return Err(ContractError::InvalidSpendingPeriod);
+    }
+
+    let mut limit = SpendingLimit {
+        amount,
+        period,
+        period_start: BlockHeight::default(),
+        spent: Amount::ZERO,
+    };
+    limit.period_start = limit.current_period_start(current_block_height);
+
+    state
+        .spending_limits
+        .entry(caller)
+        .or_default()
+        .insert(spender, limit);
+
+    Ok(HandlerResult::NewState(state))
+}
+
+// Removes the limit, cleanup empty entries like with allowances
+fn remove_spending_limit(state: &mut State, owner: Address, spender: &Address) {
+    if let Entry::Occupied(mut owner_limits) = state.spending_limits.entry(owner) {
+        owner_limits.get_mut().remove(spender);
+        if owner_limits.get().keys().len() == 0 {
+            owner_limits.remove_entry();
+        }
+    }
+}
+
+pub fn spending_limit(
+    state: State,
+    current_block_height: u128,
+    owner: Address,
+    spender: Address,
+) -> ActionResult {
+    let limit = state
+        .spending_limits
+        .get(&owner)
+        .and_then(|spenders| spenders.get(&spender))
+        .ok_or_else(|| ContractError::SpendingLimitNotSet {
+            owner: owner.clone(),
+            spender: spender.clone(),
+        })?;
+
+    let period_start = limit.current_period_start(current_block_height);
+
+    Ok(HandlerResult::QueryResponse(
+        QueryResponseMsg::SpendingLimit {
+            limit: limit.amount,
+            remaining: limit.remaining(current_block_height),
+            period_ends: BlockHeight(*period_start + limit.period),
+            owner,
+            spender,
+        },
+    ))
+}
+
+// Checks that the spender is within its spending limit for the current period
+// and records the spent amount. Allowances without a limit are not restricted.
+fn spend_limit(
+    state: &mut State,
+    current_block_height: u128,
+    owner: &Address,
+    spender: &Address,
+    amount: &Amount,
+) -> Result<(), ContractError> {
+    let limit = match state
+        .spending_limits
+        .get_mut(owner)
+        .and_then(|spenders| spenders.get_mut(spender))
+    {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    let remaining = limit.remaining(current_block_height);
+    if remaining < *amount {
+        return Err(ContractError::SpendingLimitExceeded(remaining));
+    }
+
+    let period_start = limit.current_period_start(current_block_height);
+    if limit.period_start != period_start {
+        limit.period_start = period_start;
+        limit.spent = Amount::ZERO;
+    }
+    limit.spent += *amount;
+
+    Ok(())
+}
+
 pub(super) fn spend_allowance(
     mut state: State,
+    current_block_height: u128,
     owner: &Address,
     spender: &Address,
     amount: &Amount,
@@ -80,6 +191,8 @@ pub(super) fn spend_allowance(
         return Err(ContractError::AmountMustBeHigherThanZero);
     }
 
+    spend_limit(&mut state, current_block_height, owner, spender, amount)?;
+
     // set allowances[caller][spender] = amount,
     // insert default when hashmap is missing value for a key
     let allowance = state
@@ -146,3 +259,178 @@ pub(super) fn spend_allowance(
         Ok(state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight};
+
+    use crate::action::QueryResponseMsg;
+    use crate::actions::transfer::transfer_from;
+    use crate::contract_utils::handler_result::HandlerResult;
+    use crate::error::ContractError;
+    use crate::state::State;
+    use crate::testing::{address, balance, new_state};
+
+    use super::{approve, set_spending_limit, spend_limit, spending_limit};
+
+    // alice lets the staking contract spend 100 tokens per 10 blocks, set in block 25
+    fn state() -> State {
+        new_state(set_spending_limit(
+            State::default(),
+            address("alice"),
+            25,
+            address("staking"),
+            Amount::from(100),
+            10,
+        ))
+    }
+
+    fn spend(state: &mut State, height: u128, amount: u128) -> Result<(), ContractError> {
+        spend_limit(
+            state,
+            height,
+            &address("alice"),
+            &address("staking"),
+            &Amount::from(amount),
+        )
+    }
+
+    fn remaining(state: State, height: u128) -> (Amount, BlockHeight) {
+        match spending_limit(state, height, address("alice"), address("staking")) {
+            Ok(HandlerResult::QueryResponse(QueryResponseMsg::SpendingLimit {
+                remaining,
+                period_ends,
+                ..
+            })) => (remaining, period_ends),
+            _ => unreachable!("Spending limit should have been found"),
+        }
+    }
+
+    #[test]
+    fn periods_are_aligned_to_the_period_length() {
+        let state = state();
+
+        let limit = &state.spending_limits[&address("alice")][&address("staking")];
+        assert_eq!(limit.period_start, BlockHeight(20));
+    }
+
+    #[test]
+    fn partial_spends_add_up_within_the_period() {
+        let mut state = state();
+
+        assert_eq!(spend(&mut state, 25, 60), Ok(()));
+        assert_eq!(
+            spend(&mut state, 27, 50),
+            Err(ContractError::SpendingLimitExceeded(Amount::from(40)))
+        );
+        assert_eq!(spend(&mut state, 29, 40), Ok(()));
+        assert_eq!(
+            spend(&mut state, 29, 1),
+            Err(ContractError::SpendingLimitExceeded(Amount::ZERO))
+        );
+    }
+
+    #[test]
+    fn budget_is_renewed_when_the_period_rolls_over() {
+        let mut state = state();
+
+        assert_eq!(spend(&mut state, 29, 100), Ok(()));
+        assert_eq!(spend(&mut state, 30, 100), Ok(()));
+
+        let limit = &state.spending_limits[&address("alice")][&address("staking")];
+        assert_eq!(limit.period_start, BlockHeight(30));
+        assert_eq!(limit.spent, Amount::from(100));
+    }
+
+    #[test]
+    fn query_reports_the_remaining_budget_of_the_current_period() {
+        let mut state = state();
+        spend(&mut state, 26, 30).unwrap();
+
+        assert_eq!(
+            remaining(state.clone(), 29),
+            (Amount::from(70), BlockHeight(30))
+        );
+        // nothing spent yet in the next period
+        assert_eq!(remaining(state, 33), (Amount::from(100), BlockHeight(40)));
+    }
+
+    #[test]
+    fn allowance_without_a_limit_is_not_restricted() {
+        let mut state = State::default();
+
+        assert_eq!(spend(&mut state, 25, 1_000_000), Ok(()));
+    }
+
+    #[test]
+    fn zero_amount_removes_the_limit() {
+        let state = new_state(set_spending_limit(
+            state(),
+            address("alice"),
+            26,
+            address("staking"),
+            Amount::ZERO,
+            10,
+        ));
+
+        assert!(state.spending_limits.is_empty());
+    }
+
+    // alice holds 1000 tokens and allows the staking contract to pull 500 of them
+    fn approved_state() -> State {
+        let mut state = state();
+        state.balances.insert(address("alice"), Amount::from(1000));
+        new_state(approve(
+            state,
+            address("alice"),
+            address("staking"),
+            Amount::from(500),
+        ))
+    }
+
+    fn pull(state: State, height: u128, amount: u128) -> Result<State, ContractError> {
+        match transfer_from(
+            state,
+            address("staking"),
+            height,
+            address("alice"),
+            address("bob"),
+            Amount::from(amount),
+        ) {
+            Ok(HandlerResult::NewState(state)) => Ok(state),
+            Ok(_) => unreachable!("Transfer should return the new state"),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[test]
+    fn transfer_from_is_held_to_the_spending_limit() {
+        let state = pull(approved_state(), 25, 60).unwrap();
+        assert_eq!(balance(&state, "bob"), Amount::from(60));
+
+        assert_eq!(
+            pull(state.clone(), 29, 50).err(),
+            Some(ContractError::SpendingLimitExceeded(Amount::from(40)))
+        );
+
+        let state = pull(state, 30, 50).unwrap();
+        assert_eq!(balance(&state, "alice"), Amount::from(890));
+        assert_eq!(
+            state.allowances[&address("alice")][&address("staking")],
+            Amount::from(390)
+        );
+    }
+
+    #[test]
+    fn removed_allowance_removes_the_spending_limit() {
+        let state = new_state(approve(
+            approved_state(),
+            address("alice"),
+            address("staking"),
+            Amount::ZERO,
+        ));
+
+        assert!(state.allowances.is_empty());
+        assert!(state.spending_limits.is_empty());
+    }
+}
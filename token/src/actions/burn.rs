@@ -30,19 +30,21 @@ pub fn burn(mut state: State, amount: Amount) -> ActionResult {
     Ok(HandlerResult::NewState(state))
 }
 
-pub fn burn_from(state: State, from: Address, amount: Amount) -> ActionResult {
+pub fn burn_from(
+    state: State,
+    caller: Address,
+    current_block_height: u128,
+    from: Address,
+    amount: Amount,
+) -> ActionResult {
     if amount == Amount::ZERO {
         return Err(ContractError::AmountMustBeHigherThanZero);
     }
 
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
-
     // If caller is using this function for burning tokens,
     // no need to check or change allowances.
     let mut state = if caller != from {
-        spend_allowance(state, &from, &caller, &amount)?
+        spend_allowance(state, current_block_height, &from, &caller, &amount)?
     } else {
         state
     };
@@ -0,0 +1,250 @@
+use bundlr_contracts_shared::{Address, Amount, BlockHeight, TransactionId};
+use sha2::{Digest, Sha256};
+
+use crate::action::{ActionResult, QueryResponseMsg};
+use crate::contract_utils::handler_result::HandlerResult;
+use crate::error::ContractError;
+use crate::state::{Escrow, State};
+
+use super::fee::credit;
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, ContractError> {
+    data_encoding::HEXLOWER_PERMISSIVE
+        .decode(value.as_bytes())
+        .map_err(|err| ContractError::ParseError(err.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_escrow(
+    mut state: State,
+    caller: Address,
+    id: TransactionId,
+    current_block_height: u128,
+    to: Address,
+    amount: Amount,
+    hashlock: String,
+    expiry_height: BlockHeight,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    if decode_hex(&hashlock)?.len() != 32 {
+        return Err(ContractError::InvalidHashlock(hashlock));
+    }
+
+    if *expiry_height <= current_block_height {
+        return Err(ContractError::InvalidExpiryHeight(expiry_height));
+    }
+
+    // Checking if caller has enough funds
+    let caller_balance = *state.balances.get(&caller).unwrap_or(&Amount::ZERO);
+    if caller_balance < amount {
+        return Err(ContractError::InvalidBalance(caller_balance));
+    }
+
+    // Escrowed tokens are held in the escrow entry until claimed or refunded,
+    // so they are no longer spendable by the caller.
+    state
+        .balances
+        .insert(caller.clone(), caller_balance - amount);
+
+    state.escrows.insert(
+        id,
+        Escrow {
+            from: caller,
+            to,
+            amount,
+            hashlock: hashlock.to_lowercase(),
+            expiry_height,
+        },
+    );
+
+    state.decimals_locked = true;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn claim(
+    mut state: State,
+    current_block_height: u128,
+    id: TransactionId,
+    preimage: String,
+) -> ActionResult {
+    let escrow = state
+        .escrows
+        .get(&id)
+        .ok_or_else(|| ContractError::EscrowNotFound(id.clone()))?;
+
+    if current_block_height >= *escrow.expiry_height {
+        return Err(ContractError::EscrowExpired(id));
+    }
+
+    let hash = Sha256::digest(decode_hex(&preimage)?);
+    if data_encoding::HEXLOWER.encode(&hash) != escrow.hashlock {
+        return Err(ContractError::InvalidPreimage);
+    }
+
+    // We can safely unwrap here, the entry was checked above
+    let escrow = state.escrows.remove(&id).unwrap();
+
+    credit(&mut state, &escrow.from, escrow.to, escrow.amount);
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn refund(mut state: State, current_block_height: u128, id: TransactionId) -> ActionResult {
+    let escrow = state
+        .escrows
+        .get(&id)
+        .ok_or_else(|| ContractError::EscrowNotFound(id.clone()))?;
+
+    if current_block_height < *escrow.expiry_height {
+        return Err(ContractError::EscrowNotExpired(escrow.expiry_height));
+    }
+
+    // We can safely unwrap here, the entry was checked above
+    let escrow = state.escrows.remove(&id).unwrap();
+
+    let from_balance = *state.balances.get(&escrow.from).unwrap_or(&Amount::ZERO);
+    state
+        .balances
+        .insert(escrow.from, from_balance + escrow.amount);
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn escrow(mut state: State, id: TransactionId) -> ActionResult {
+    let escrow = state
+        .escrows
+        .remove(&id)
+        .ok_or(ContractError::EscrowNotFound(id))?;
+
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::Escrow(
+        escrow,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::{Amount, BlockHeight, TransactionId};
+    use sha2::{Digest, Sha256};
+
+    use crate::error::ContractError;
+    use crate::state::State;
+    use crate::testing::{address, balance, new_state};
+
+    use super::{claim, create_escrow, refund};
+
+    const PREIMAGE: &str = "0123456789abcdef";
+
+    fn id() -> TransactionId {
+        "escrow_tx".parse().unwrap()
+    }
+
+    fn hashlock() -> String {
+        let preimage = data_encoding::HEXLOWER.decode(PREIMAGE.as_bytes()).unwrap();
+        data_encoding::HEXLOWER.encode(&Sha256::digest(preimage))
+    }
+
+    // alice has escrowed 400 of her 1000 tokens to bob until block 200
+    fn state() -> State {
+        let state = State {
+            ticker: "TEST".to_string(),
+            total_supply: Amount::from(1000),
+            balances: [(address("alice"), Amount::from(1000))]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        new_state(create_escrow(
+            state,
+            address("alice"),
+            id(),
+            100,
+            address("bob"),
+            Amount::from(400),
+            hashlock(),
+            BlockHeight(200),
+        ))
+    }
+
+    #[test]
+    fn escrowed_amount_is_removed_from_the_spendable_balance() {
+        let state = state();
+
+        assert_eq!(balance(&state, "alice"), Amount::from(600));
+        assert_eq!(state.escrows.get(&id()).unwrap().amount, Amount::from(400));
+    }
+
+    #[test]
+    fn escrow_cannot_exceed_the_balance() {
+        let result = create_escrow(
+            state(),
+            address("alice"),
+            "other_tx".parse().unwrap(),
+            100,
+            address("bob"),
+            Amount::from(601),
+            hashlock(),
+            BlockHeight(200),
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidBalance(Amount::from(600)))
+        );
+    }
+
+    #[test]
+    fn claim_with_the_preimage_credits_the_recipient() {
+        let state = new_state(claim(state(), 150, id(), PREIMAGE.to_string()));
+
+        assert_eq!(balance(&state, "alice"), Amount::from(600));
+        assert_eq!(balance(&state, "bob"), Amount::from(400));
+        assert!(state.escrows.is_empty());
+    }
+
+    #[test]
+    fn claim_with_a_wrong_preimage_fails() {
+        let result = claim(state(), 150, id(), "0123456789abcdee".to_string());
+
+        assert_eq!(result.err(), Some(ContractError::InvalidPreimage));
+    }
+
+    #[test]
+    fn claim_after_expiry_fails() {
+        let result = claim(state(), 200, id(), PREIMAGE.to_string());
+
+        assert_eq!(result.err(), Some(ContractError::EscrowExpired(id())));
+    }
+
+    #[test]
+    fn escrow_can_be_claimed_only_once() {
+        let state = new_state(claim(state(), 150, id(), PREIMAGE.to_string()));
+
+        let result = claim(state, 151, id(), PREIMAGE.to_string());
+
+        assert_eq!(result.err(), Some(ContractError::EscrowNotFound(id())));
+    }
+
+    #[test]
+    fn refund_before_expiry_fails() {
+        let result = refund(state(), 199, id());
+
+        assert_eq!(
+            result.err(),
+            Some(ContractError::EscrowNotExpired(BlockHeight(200)))
+        );
+    }
+
+    #[test]
+    fn refund_after_expiry_returns_the_amount_to_the_sender() {
+        let state = new_state(refund(state(), 200, id()));
+
+        assert_eq!(balance(&state, "alice"), Amount::from(1000));
+        assert_eq!(balance(&state, "bob"), Amount::ZERO);
+        assert!(state.escrows.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use bundlr_contracts_shared::{Address, Amount};
+
+use crate::action::{ActionResult, QueryResponseMsg};
+use crate::contract_utils::handler_result::HandlerResult;
+use crate::error::ContractError;
+use crate::state::{FeeConfig, State, BASIS_POINTS};
+
+pub fn set_fee_config(
+    mut state: State,
+    caller: Address,
+    fee_bps: u16,
+    treasury: Address,
+    exempt: HashSet<Address>,
+) -> ActionResult {
+    if caller != state.owner {
+        return Err(ContractError::Forbidden);
+    }
+
+    if fee_bps > BASIS_POINTS {
+        return Err(ContractError::InvalidFee(fee_bps));
+    }
+
+    state.fee_config = Some(FeeConfig {
+        fee_bps,
+        treasury,
+        exempt,
+    });
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn fee_config(state: State) -> ActionResult {
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::FeeConfig(
+        state.fee_config,
+    )))
+}
+
+/// Credits the transferred amount to the recipient, less the transfer fee
+/// which is routed to the treasury.
+pub(super) fn credit(state: &mut State, from: &Address, to: Address, amount: Amount) {
+    let fee = state
+        .fee_config
+        .as_ref()
+        .map_or(Amount::ZERO, |config| config.fee(from, &to, amount));
+
+    if fee != Amount::ZERO {
+        // We can safely unwrap here, fee can be non-zero only when fee is configured
+        let treasury = state.fee_config.as_ref().unwrap().treasury.clone();
+        let treasury_balance = *state.balances.get(&treasury).unwrap_or(&Amount::ZERO);
+        state.balances.insert(treasury, treasury_balance + fee);
+    }
+
+    let to_balance = *state.balances.get(&to).unwrap_or(&Amount::ZERO);
+    state.balances.insert(to, to_balance + amount - fee);
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::Amount;
+
+    use crate::error::ContractError;
+    use crate::state::{FeeConfig, State};
+    use crate::testing::{address, balance, new_state};
+
+    use super::{credit, set_fee_config};
+
+    fn fee_config(fee_bps: u16) -> FeeConfig {
+        FeeConfig {
+            fee_bps,
+            treasury: address("treasury"),
+            exempt: [address("bundlers")].into_iter().collect(),
+        }
+    }
+
+    fn fee(fee_bps: u16, amount: u128) -> Amount {
+        fee_config(fee_bps).fee(&address("alice"), &address("bob"), Amount::from(amount))
+    }
+
+    #[test]
+    fn fee_is_rounded_down() {
+        assert_eq!(fee(30, 10_000), Amount::from(30));
+        assert_eq!(fee(30, 10_333), Amount::from(30));
+        assert_eq!(fee(30, 333), Amount::from(0));
+        assert_eq!(fee(1, 9_999), Amount::from(0));
+    }
+
+    #[test]
+    fn zero_fee_takes_nothing() {
+        assert_eq!(fee(0, 1_000_000), Amount::ZERO);
+    }
+
+    #[test]
+    fn full_fee_takes_the_whole_amount_without_overflowing() {
+        assert_eq!(fee(10_000, 1_000_000), Amount::from(1_000_000));
+        assert_eq!(fee(10_000, u128::MAX), Amount::from(u128::MAX));
+    }
+
+    #[test]
+    fn exempt_sender_or_recipient_is_not_charged() {
+        let config = fee_config(100);
+        let amount = Amount::from(1_000);
+
+        assert_eq!(
+            config.fee(&address("bundlers"), &address("bob"), amount),
+            Amount::ZERO
+        );
+        assert_eq!(
+            config.fee(&address("alice"), &address("bundlers"), amount),
+            Amount::ZERO
+        );
+    }
+
+    #[test]
+    fn credit_routes_the_fee_to_the_treasury() {
+        let mut state = State {
+            fee_config: Some(fee_config(100)),
+            ..Default::default()
+        };
+
+        credit(
+            &mut state,
+            &address("alice"),
+            address("bob"),
+            Amount::from(1_000),
+        );
+
+        assert_eq!(balance(&state, "bob"), Amount::from(990));
+        assert_eq!(balance(&state, "treasury"), Amount::from(10));
+    }
+
+    #[test]
+    fn credit_without_fee_config_credits_the_whole_amount() {
+        let mut state = State::default();
+
+        credit(
+            &mut state,
+            &address("alice"),
+            address("bob"),
+            Amount::from(1_000),
+        );
+
+        assert_eq!(balance(&state, "bob"), Amount::from(1_000));
+        assert_eq!(state.balances.len(), 1);
+    }
+
+    #[test]
+    fn treasury_receiving_a_transfer_gets_the_whole_amount() {
+        let mut state = State {
+            fee_config: Some(fee_config(100)),
+            balances: [(address("treasury"), Amount::from(5))]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        credit(
+            &mut state,
+            &address("alice"),
+            address("treasury"),
+            Amount::from(1_000),
+        );
+
+        assert_eq!(balance(&state, "treasury"), Amount::from(1_005));
+    }
+
+    fn owned_state() -> State {
+        State {
+            owner: address("owner"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_owner_can_set_the_fee_config() {
+        let result = set_fee_config(
+            owned_state(),
+            address("alice"),
+            30,
+            address("treasury"),
+            Default::default(),
+        );
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+
+        let state = new_state(set_fee_config(
+            owned_state(),
+            address("owner"),
+            30,
+            address("treasury"),
+            [address("bundlers")].into_iter().collect(),
+        ));
+        let config = state.fee_config.unwrap();
+        assert_eq!(config.fee_bps, 30);
+        assert_eq!(config.treasury, address("treasury"));
+        assert!(config.exempt.contains(&address("bundlers")));
+    }
+
+    #[test]
+    fn fee_cannot_exceed_the_whole_amount() {
+        let result = set_fee_config(
+            owned_state(),
+            address("owner"),
+            10_001,
+            address("treasury"),
+            Default::default(),
+        );
+        assert_eq!(result.err(), Some(ContractError::InvalidFee(10_001)));
+
+        let state = new_state(set_fee_config(
+            owned_state(),
+            address("owner"),
+            10_000,
+            address("treasury"),
+            Default::default(),
+        ));
+        assert_eq!(state.fee_config.unwrap().fee_bps, 10_000);
+    }
+}
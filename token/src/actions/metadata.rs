@@ -0,0 +1,182 @@
+use bundlr_contracts_shared::Address;
+
+use crate::action::ActionResult;
+use crate::contract_utils::handler_result::HandlerResult;
+use crate::error::ContractError;
+use crate::state::State;
+
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_TICKER_LENGTH: usize = 10;
+const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+fn validate_name(name: &str) -> Result<(), ContractError> {
+    if name.trim().is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ContractError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+fn validate_ticker(ticker: &str) -> Result<(), ContractError> {
+    // Tickers are short and restricted to uppercase ASCII letters and digits
+    // so that wallets can render them without surprises.
+    if ticker.is_empty()
+        || ticker.len() > MAX_TICKER_LENGTH
+        || !ticker
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Err(ContractError::InvalidTicker(ticker.to_string()));
+    }
+    Ok(())
+}
+
+pub fn update_metadata(
+    mut state: State,
+    caller: Address,
+    name: Option<String>,
+    ticker: Option<String>,
+    logo: Option<String>,
+    description: Option<String>,
+    decimals: Option<u8>,
+) -> ActionResult {
+    if caller != state.owner {
+        return Err(ContractError::Forbidden);
+    }
+
+    if let Some(name) = name {
+        validate_name(&name)?;
+        state.name = Some(name);
+    }
+
+    if let Some(ticker) = ticker {
+        validate_ticker(&ticker)?;
+        state.ticker = ticker;
+    }
+
+    if let Some(logo) = logo {
+        // Empty value clears the logo
+        state.logo = if logo.is_empty() { None } else { Some(logo) };
+    }
+
+    if let Some(description) = description {
+        if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(ContractError::InvalidDescription);
+        }
+        state.description = if description.is_empty() {
+            None
+        } else {
+            Some(description)
+        };
+    }
+
+    if let Some(decimals) = decimals {
+        if decimals != state.decimals && state.decimals_locked {
+            return Err(ContractError::DecimalsLocked);
+        }
+        state.decimals = decimals;
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use bundlr_contracts_shared::Address;
+
+    use crate::contract_utils::handler_result::HandlerResult;
+    use crate::error::ContractError;
+    use crate::state::State;
+
+    use super::{update_metadata, validate_ticker};
+
+    fn owner() -> Address {
+        "owner".parse().unwrap()
+    }
+
+    fn update_decimals(state: State, decimals: u8) -> Result<State, ContractError> {
+        match update_metadata(state, owner(), None, None, None, None, Some(decimals)) {
+            Ok(HandlerResult::NewState(state)) => Ok(state),
+            Ok(_) => unreachable!("Update should return the new state"),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[test]
+    fn ticker_has_to_be_short_uppercase_alphanumeric() {
+        assert_eq!(validate_ticker("BNDLR"), Ok(()));
+        assert_eq!(validate_ticker("AR2"), Ok(()));
+        assert_eq!(validate_ticker("ABCDEFGHIJ"), Ok(()));
+
+        for ticker in ["", "ABCDEFGHIJK", "bndlr", "BND LR", "BND-LR", "ÅR"] {
+            assert_eq!(
+                validate_ticker(ticker),
+                Err(ContractError::InvalidTicker(ticker.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn only_owner_can_update_metadata() {
+        let state = State {
+            owner: owner(),
+            ..Default::default()
+        };
+
+        let result = update_metadata(
+            state,
+            "other".parse().unwrap(),
+            Some("Token".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        assert_eq!(result.err(), Some(ContractError::Forbidden));
+    }
+
+    #[test]
+    fn decimals_can_be_changed_until_locked() {
+        let state = State {
+            owner: owner(),
+            decimals: 12,
+            ..Default::default()
+        };
+
+        let state = update_decimals(state, 18).unwrap();
+        assert_eq!(state.decimals, 18);
+
+        let state = State {
+            decimals_locked: true,
+            ..state
+        };
+        assert_eq!(
+            update_decimals(state.clone(), 6).err(),
+            Some(ContractError::DecimalsLocked)
+        );
+        // setting the same value is not a change
+        assert!(update_decimals(state, 18).is_ok());
+    }
+
+    #[test]
+    fn state_without_the_lock_flag_is_locked() {
+        let state: State = serde_json::from_str(
+            r#"{
+                "ticker": "TEST",
+                "name": "Test",
+                "decimals": 12,
+                "totalSupply": "1000",
+                "owner": "owner",
+                "balances": { "owner": "600", "holder": "400" },
+                "allowances": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert!(state.decimals_locked);
+        assert_eq!(
+            update_decimals(state, 18).err(),
+            Some(ContractError::DecimalsLocked)
+        );
+    }
+}
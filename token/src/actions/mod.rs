@@ -1,4 +1,8 @@
 pub mod allowance;
 pub mod burn;
+pub mod escrow;
+pub mod fee;
+pub mod metadata;
 pub mod queries;
+pub mod relayed;
 pub mod transfer;
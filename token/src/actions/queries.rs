@@ -31,3 +31,15 @@ pub fn balance(state: State, target: &Address) -> ActionResult {
         target: target.clone(),
     }))
 }
+
+pub fn info(state: State) -> ActionResult {
+    Ok(QueryResponse(QueryResponseMsg::Info {
+        ticker: state.ticker,
+        name: state.name,
+        logo: state.logo,
+        description: state.description,
+        decimals: state.decimals,
+        total_supply: state.total_supply,
+        owner: state.owner,
+    }))
+}
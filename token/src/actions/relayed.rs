@@ -0,0 +1,410 @@
+use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use rsa::{BigUint, Pss, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::action::{ActionResult, QueryResponseMsg};
+use crate::contract_utils::handler_result::HandlerResult;
+use crate::error::ContractError;
+use crate::state::State;
+
+use super::fee::credit;
+
+// Arweave wallets sign using RSA-PSS with SHA-256 and 32 byte salt
+const ARWEAVE_PSS_SALT_LENGTH: usize = 32;
+const ARWEAVE_PUBLIC_EXPONENT: u32 = 65537;
+
+/// Canonical payload the holder signs to authorize a relayed transfer, the
+/// contract ID keeps it from being replayed against another deployment.
+pub fn relayed_transfer_message(
+    contract_id: &str,
+    from: &Address,
+    to: &Address,
+    amount: &Amount,
+    nonce: u64,
+    deadline: &BlockHeight,
+) -> String {
+    format!(
+        "bundlr-token:relayedTransfer:{}:{}:{}:{}:{}:{}",
+        contract_id, &**from, &**to, **amount, nonce, **deadline
+    )
+}
+
+fn is_ethereum_address(address: &Address) -> bool {
+    address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Verifies RSA-PSS signature made with an Arweave wallet, whose address is the
+// SHA-256 hash of the public key modulus
+fn verify_arweave_signature(
+    from: &Address,
+    public_key: &str,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), ContractError> {
+    let modulus = data_encoding::BASE64URL_NOPAD
+        .decode(public_key.as_bytes())
+        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+    let address = data_encoding::BASE64URL_NOPAD.encode(&Sha256::digest(&modulus));
+    if address != **from {
+        return Err(ContractError::InvalidPublicKey);
+    }
+
+    let public_key = RsaPublicKey::new(
+        BigUint::from_bytes_be(&modulus),
+        BigUint::from(ARWEAVE_PUBLIC_EXPONENT),
+    )
+    .map_err(|_| ContractError::InvalidPublicKey)?;
+
+    public_key
+        .verify(
+            Pss::new_with_salt::<Sha256>(ARWEAVE_PSS_SALT_LENGTH),
+            &Sha256::digest(message),
+            signature,
+        )
+        .map_err(|_| ContractError::InvalidSignature)
+}
+
+// Verifies secp256k1 signature made with an Ethereum wallet (EIP-191
+// personal_sign), the recovered signer has to match the sender
+fn verify_ethereum_signature(
+    from: &Address,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), ContractError> {
+    if signature.len() != 65 {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    let prehash = Keccak256::new()
+        .chain_update(format!("\x19Ethereum Signed Message:\n{}", message.len()))
+        .chain_update(message)
+        .finalize();
+
+    let recovery_id = match signature[64] {
+        v @ 27..=28 => v - 27,
+        v => v,
+    };
+    let recovery_id = RecoveryId::from_byte(recovery_id).ok_or(ContractError::InvalidSignature)?;
+    let signature =
+        Signature::from_slice(&signature[..64]).map_err(|_| ContractError::InvalidSignature)?;
+
+    let verifying_key = VerifyingKey::recover_from_prehash(&prehash, &signature, recovery_id)
+        .map_err(|_| ContractError::InvalidSignature)?;
+
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+    let signer = data_encoding::HEXLOWER.encode(&hash[12..]);
+
+    if !from[2..].eq_ignore_ascii_case(&signer) {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+pub fn verify_signature(
+    from: &Address,
+    public_key: Option<&str>,
+    message: &str,
+    signature: &str,
+) -> Result<(), ContractError> {
+    if is_ethereum_address(from) {
+        let signature = data_encoding::HEXLOWER_PERMISSIVE
+            .decode(signature.trim_start_matches("0x").as_bytes())
+            .map_err(|err| ContractError::ParseError(err.to_string()))?;
+        verify_ethereum_signature(from, message.as_bytes(), &signature)
+    } else {
+        let public_key = public_key.ok_or(ContractError::InvalidPublicKey)?;
+        let signature = data_encoding::BASE64URL_NOPAD
+            .decode(signature.as_bytes())
+            .map_err(|err| ContractError::ParseError(err.to_string()))?;
+        verify_arweave_signature(from, public_key, message.as_bytes(), &signature)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn relayed_transfer(
+    mut state: State,
+    contract_id: &str,
+    current_block_height: u128,
+    from: Address,
+    to: Address,
+    amount: Amount,
+    nonce: u64,
+    deadline: BlockHeight,
+    public_key: Option<String>,
+    signature: String,
+) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::AmountMustBeHigherThanZero);
+    }
+
+    if current_block_height > *deadline {
+        return Err(ContractError::DeadlinePassed(deadline));
+    }
+
+    let expected_nonce = *state.nonces.get(&from).unwrap_or(&0);
+    if nonce != expected_nonce {
+        return Err(ContractError::InvalidNonce(expected_nonce));
+    }
+
+    let message = relayed_transfer_message(contract_id, &from, &to, &amount, nonce, &deadline);
+    verify_signature(&from, public_key.as_deref(), &message, &signature)?;
+
+    let balances = &mut state.balances;
+
+    // Checking if signer has enough funds
+    let from_balance = *balances.get(&from).unwrap_or(&Amount::ZERO);
+    if from_balance < amount {
+        return Err(ContractError::InvalidBalance(from_balance));
+    }
+
+    balances.insert(from.clone(), from_balance - amount);
+
+    credit(&mut state, &from, to, amount);
+
+    state.nonces.insert(from, nonce + 1);
+    state.decimals_locked = true;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+pub fn nonce(state: State, address: Address) -> ActionResult {
+    let nonce = *state.nonces.get(&address).unwrap_or(&0);
+
+    Ok(HandlerResult::QueryResponse(QueryResponseMsg::Nonce {
+        nonce,
+        address,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bundlr_contracts_shared::{Address, Amount, BlockHeight};
+    use k256::ecdsa::SigningKey;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use rsa::{traits::PublicKeyParts, Pss, RsaPrivateKey};
+    use sha2::Sha256;
+    use sha3::{Digest, Keccak256};
+
+    use crate::action::ActionResult;
+    use crate::error::ContractError;
+    use crate::state::{FeeConfig, State};
+    use crate::testing::{address, balance, new_state};
+
+    use super::{relayed_transfer, relayed_transfer_message, verify_signature};
+
+    const CONTRACT_ID: &str = "token_contract";
+
+    fn ethereum_signer() -> (SigningKey, Address) {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+        let address = format!("0x{}", data_encoding::HEXLOWER.encode(&hash[12..]));
+        (signing_key, Address::from_str(&address).unwrap())
+    }
+
+    fn sign(signing_key: &SigningKey, message: &str) -> String {
+        let digest = Keccak256::new()
+            .chain_update(format!("\x19Ethereum Signed Message:\n{}", message.len()))
+            .chain_update(message.as_bytes());
+        let (signature, recovery_id) = signing_key.sign_digest_recoverable(digest).unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte() + 27);
+        format!("0x{}", data_encoding::HEXLOWER.encode(&bytes))
+    }
+
+    // Arweave wallet with its address and the base64url encoded modulus the
+    // holder passes as the public key
+    fn arweave_signer(seed: u64) -> (RsaPrivateKey, Address, String) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let private_key = RsaPrivateKey::new(&mut rng, 1024).unwrap();
+        let modulus = private_key.n().to_bytes_be();
+        let wallet = data_encoding::BASE64URL_NOPAD.encode(&Sha256::digest(&modulus));
+        let public_key = data_encoding::BASE64URL_NOPAD.encode(&modulus);
+        (private_key, address(&wallet), public_key)
+    }
+
+    fn sign_arweave(private_key: &RsaPrivateKey, message: &str) -> String {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let signature = private_key
+            .sign_with_rng(
+                &mut rng,
+                Pss::new_with_salt::<Sha256>(32),
+                &Sha256::digest(message.as_bytes()),
+            )
+            .unwrap();
+        data_encoding::BASE64URL_NOPAD.encode(&signature)
+    }
+
+    fn message(from: &Address, nonce: u64) -> String {
+        relayed_transfer_message(
+            CONTRACT_ID,
+            from,
+            &address("to"),
+            &Amount::from(100),
+            nonce,
+            &BlockHeight(1000),
+        )
+    }
+
+    // holder has 150 tokens
+    fn funded(holder: &Address) -> State {
+        State {
+            ticker: "TEST".to_string(),
+            total_supply: Amount::from(150),
+            balances: [(holder.clone(), Amount::from(150))].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    // Relays a transfer of 100 tokens to "to" with a deadline at block 1000
+    fn relay(
+        state: State,
+        height: u128,
+        from: &Address,
+        nonce: u64,
+        public_key: Option<String>,
+        signature: String,
+    ) -> ActionResult {
+        relayed_transfer(
+            state,
+            CONTRACT_ID,
+            height,
+            from.clone(),
+            address("to"),
+            Amount::from(100),
+            nonce,
+            BlockHeight(1000),
+            public_key,
+            signature,
+        )
+    }
+
+    #[test]
+    fn ethereum_signature_from_the_holder_is_accepted() {
+        let (signing_key, from) = ethereum_signer();
+        let message = message(&from, 0);
+        let signature = sign(&signing_key, &message);
+
+        assert!(verify_signature(&from, None, &message, &signature).is_ok());
+    }
+
+    #[test]
+    fn ethereum_signature_over_different_payload_is_rejected() {
+        let (signing_key, from) = ethereum_signer();
+        let signature = sign(&signing_key, &message(&from, 0));
+
+        assert!(matches!(
+            verify_signature(&from, None, &message(&from, 1), &signature),
+            Err(ContractError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn arweave_signature_requires_public_key() {
+        let from = address("uhE-QeYS8i4pmUtnxQyHD7dzXFNaJ9oMK-IM-QPNY6M");
+
+        assert!(matches!(
+            verify_signature(&from, None, &message(&from, 0), "c2lnbmF0dXJl"),
+            Err(ContractError::InvalidPublicKey)
+        ));
+    }
+
+    #[test]
+    fn relayed_transfer_signed_with_arweave_wallet_is_accepted() {
+        let (private_key, from, public_key) = arweave_signer(7);
+        let signature = sign_arweave(&private_key, &message(&from, 0));
+
+        let state = new_state(relay(
+            funded(&from),
+            900,
+            &from,
+            0,
+            Some(public_key.clone()),
+            signature.clone(),
+        ));
+        assert_eq!(balance(&state, &from), Amount::from(50));
+        assert_eq!(balance(&state, "to"), Amount::from(100));
+
+        // Public key of another wallet does not match the address
+        let (_, _, other_public_key) = arweave_signer(8);
+        assert_eq!(
+            relay(
+                funded(&from),
+                900,
+                &from,
+                0,
+                Some(other_public_key),
+                signature
+            )
+            .err(),
+            Some(ContractError::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn relayed_transfer_cannot_be_replayed() {
+        let (signing_key, from) = ethereum_signer();
+        let signature = sign(&signing_key, &message(&from, 0));
+
+        let state = new_state(relay(funded(&from), 900, &from, 0, None, signature.clone()));
+        assert_eq!(state.nonces[&from], 1);
+
+        assert_eq!(
+            relay(state, 900, &from, 0, None, signature).err(),
+            Some(ContractError::InvalidNonce(1))
+        );
+    }
+
+    #[test]
+    fn relayed_transfer_after_the_deadline_is_rejected() {
+        let (signing_key, from) = ethereum_signer();
+        let signature = sign(&signing_key, &message(&from, 0));
+
+        assert!(relay(funded(&from), 1000, &from, 0, None, signature.clone()).is_ok());
+        assert_eq!(
+            relay(funded(&from), 1001, &from, 0, None, signature).err(),
+            Some(ContractError::DeadlinePassed(BlockHeight(1000)))
+        );
+    }
+
+    #[test]
+    fn relayed_transfer_requires_the_balance() {
+        let (signing_key, from) = ethereum_signer();
+        let signature = sign(&signing_key, &message(&from, 0));
+
+        let mut state = funded(&from);
+        state.balances.insert(from.clone(), Amount::from(99));
+
+        assert_eq!(
+            relay(state, 900, &from, 0, None, signature).err(),
+            Some(ContractError::InvalidBalance(Amount::from(99)))
+        );
+    }
+
+    #[test]
+    fn relayed_transfer_pays_the_transfer_fee() {
+        let (signing_key, from) = ethereum_signer();
+        let signature = sign(&signing_key, &message(&from, 0));
+
+        let mut state = funded(&from);
+        state.fee_config = Some(FeeConfig {
+            fee_bps: 100,
+            treasury: address("treasury"),
+            exempt: Default::default(),
+        });
+
+        let state = new_state(relay(state, 900, &from, 0, None, signature));
+        assert_eq!(balance(&state, &from), Amount::from(50));
+        assert_eq!(balance(&state, "to"), Amount::from(99));
+        assert_eq!(balance(&state, "treasury"), Amount::from(1));
+    }
+}
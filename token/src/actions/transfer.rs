@@ -7,6 +7,7 @@ use crate::error::ContractError;
 use crate::state::State;
 
 use super::allowance::spend_allowance;
+use super::fee::credit;
 
 pub fn transfer(mut state: State, to: Address, amount: Amount) -> ActionResult {
     if amount == Amount::ZERO {
@@ -24,31 +25,32 @@ pub fn transfer(mut state: State, to: Address, amount: Amount) -> ActionResult {
         return Err(ContractError::InvalidBalance(caller_balance));
     }
 
-    balances.insert(caller, caller_balance - amount);
+    balances.insert(caller.clone(), caller_balance - amount);
 
-    let target_balance = *balances.get(&to).unwrap_or(&Amount::ZERO);
-    balances.insert(to, target_balance + amount);
+    credit(&mut state, &caller, to, amount);
+
+    state.decimals_locked = true;
 
     Ok(HandlerResult::NewState(state))
 }
 
-pub fn transfer_from(state: State, from: Address, to: Address, amount: Amount) -> ActionResult {
+pub fn transfer_from(
+    state: State,
+    caller: Address,
+    current_block_height: u128,
+    from: Address,
+    to: Address,
+    amount: Amount,
+) -> ActionResult {
     if amount == Amount::ZERO {
         log("[transferFrom] Amount is zero");
         return Err(ContractError::AmountMustBeHigherThanZero);
     }
 
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| {
-            log(&format!("[transferFrom] Failed to parse address - {}", err));
-            ContractError::ParseError(err.to_string())
-        })?;
-
     // If caller is using this function for transferring tokens,
     // no need to check or change allowances.
     let mut state = if caller != from {
-        spend_allowance(state, &from, &caller, &amount)?
+        spend_allowance(state, current_block_height, &from, &caller, &amount)?
     } else {
         state
     };
@@ -62,10 +64,11 @@ pub fn transfer_from(state: State, from: Address, to: Address, amount: Amount) -
         return Err(ContractError::InvalidBalance(from_balance));
     }
 
-    balances.insert(from, from_balance - amount);
+    balances.insert(from.clone(), from_balance - amount);
+
+    credit(&mut state, &from, to, amount);
 
-    let to_balance = *balances.get(&to).unwrap_or(&Amount::ZERO);
-    balances.insert(to, to_balance + amount);
+    state.decimals_locked = true;
 
     Ok(HandlerResult::NewState(state))
 }
@@ -1,15 +1,34 @@
 use std::str::FromStr;
 
-use bundlr_contracts_shared::Address;
+use bundlr_contracts_shared::contract_utils::js_imports::{
+    Block, Contract, SmartWeave, Transaction,
+};
+use bundlr_contracts_shared::{Address, TransactionId};
 
 use crate::action::{Action, ActionResult};
-use crate::actions::allowance::{allowance, approve};
+use crate::actions::allowance::{allowance, approve, set_spending_limit, spending_limit};
 use crate::actions::burn::{burn, burn_from};
-use crate::actions::queries::{balance, decimals, name, symbol, total_supply};
+use crate::actions::escrow::{claim, create_escrow, escrow, refund};
+use crate::actions::fee::{fee_config, set_fee_config};
+use crate::actions::metadata::update_metadata;
+use crate::actions::queries::{balance, decimals, info, name, symbol, total_supply};
+use crate::actions::relayed::{nonce, relayed_transfer};
 use crate::actions::transfer::{transfer, transfer_from};
 use crate::error::ContractError;
 use crate::state::State;
 
+fn caller() -> Result<Address, ContractError> {
+    SmartWeave::caller()
+        .parse::<Address>()
+        .map_err(|err| ContractError::ParseError(err.to_string()))
+}
+
+fn transaction_id() -> Result<TransactionId, ContractError> {
+    Transaction::id()
+        .parse::<TransactionId>()
+        .map_err(|err| ContractError::ParseError(err.to_string()))
+}
+
 pub async fn handle(current_state: State, action: Action) -> ActionResult {
     match action {
         Action::BalanceOf { target } => {
@@ -23,12 +42,53 @@ pub async fn handle(current_state: State, action: Action) -> ActionResult {
         Action::Symbol => symbol(current_state),
         Action::Decimals => decimals(current_state),
         Action::TotalSupply => total_supply(current_state),
+        Action::Info => info(current_state),
+        Action::FeeConfig => fee_config(current_state),
+        Action::SetFeeConfig {
+            fee_bps,
+            treasury,
+            exempt,
+        } => match treasury.parse() {
+            Ok(treasury) => {
+                let exempt = exempt
+                    .into_iter()
+                    .map(|address| {
+                        address
+                            .parse()
+                            .map_err(|_| ContractError::InvalidAddress(address))
+                    })
+                    .collect::<Result<_, _>>()?;
+                set_fee_config(current_state, caller()?, fee_bps, treasury, exempt)
+            }
+            Err(_) => Err(ContractError::InvalidAddress(treasury)),
+        },
+        Action::UpdateMetadata {
+            name,
+            ticker,
+            logo,
+            description,
+            decimals,
+        } => update_metadata(
+            current_state,
+            caller()?,
+            name,
+            ticker,
+            logo,
+            description,
+            decimals,
+        ),
         Action::Burn { amount } => match amount.parse() {
             Ok(amount) => burn(current_state, amount),
             Err(err) => Err(ContractError::ParseError(err.to_string())),
         },
         Action::BurnFrom { from, amount } => match (from.parse(), amount.parse()) {
-            (Ok(from), Ok(amount)) => burn_from(current_state, from, amount),
+            (Ok(from), Ok(amount)) => burn_from(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                from,
+                amount,
+            ),
             (Err(_), _) => Err(ContractError::InvalidAddress(from)),
             (_, Err(err)) => Err(ContractError::ParseError(err.to_string())),
         },
@@ -39,14 +99,21 @@ pub async fn handle(current_state: State, action: Action) -> ActionResult {
         },
         Action::TransferFrom { from, to, amount } => {
             match (from.parse(), to.parse(), amount.parse()) {
-                (Ok(from), Ok(to), Ok(amount)) => transfer_from(current_state, from, to, amount),
+                (Ok(from), Ok(to), Ok(amount)) => transfer_from(
+                    current_state,
+                    caller()?,
+                    Block::height() as u128,
+                    from,
+                    to,
+                    amount,
+                ),
                 (Err(_), _, _) => Err(ContractError::InvalidAddress(from)),
                 (_, Err(_), _) => Err(ContractError::InvalidAddress(to)),
                 (_, _, Err(err)) => Err(ContractError::ParseError(err.to_string())),
             }
         }
         Action::Approve { spender, amount } => match (spender.parse(), amount.parse()) {
-            (Ok(spender), Ok(amount)) => approve(current_state, spender, amount),
+            (Ok(spender), Ok(amount)) => approve(current_state, caller()?, spender, amount),
             (Err(_), _) => Err(ContractError::InvalidAddress(spender)),
             (_, Err(err)) => Err(ContractError::ParseError(err.to_string())),
         },
@@ -55,5 +122,91 @@ pub async fn handle(current_state: State, action: Action) -> ActionResult {
             (_, Err(_)) => Err(ContractError::InvalidAddress(owner)),
             (Err(_), _) => Err(ContractError::InvalidAddress(spender)),
         },
+        Action::SetSpendingLimit {
+            spender,
+            amount,
+            period,
+        } => match (spender.parse(), amount.parse(), period.parse()) {
+            (Ok(spender), Ok(amount), Ok(period)) => set_spending_limit(
+                current_state,
+                caller()?,
+                Block::height() as u128,
+                spender,
+                amount,
+                period,
+            ),
+            (Err(_), _, _) => Err(ContractError::InvalidAddress(spender)),
+            (_, Err(err), _) => Err(ContractError::ParseError(err.to_string())),
+            (_, _, Err(err)) => Err(ContractError::ParseError(err.to_string())),
+        },
+        Action::SpendingLimit { owner, spender } => match (owner.parse(), spender.parse()) {
+            (Ok(owner), Ok(spender)) => {
+                spending_limit(current_state, Block::height() as u128, owner, spender)
+            }
+            (Err(_), _) => Err(ContractError::InvalidAddress(owner)),
+            (_, Err(_)) => Err(ContractError::InvalidAddress(spender)),
+        },
+        Action::RelayedTransfer {
+            from,
+            to,
+            amount,
+            nonce,
+            deadline,
+            public_key,
+            signature,
+        } => match (from.parse(), to.parse(), amount.parse(), deadline.parse()) {
+            (Ok(from), Ok(to), Ok(amount), Ok(deadline)) => relayed_transfer(
+                current_state,
+                &Contract::id(),
+                Block::height() as u128,
+                from,
+                to,
+                amount,
+                nonce,
+                deadline,
+                public_key,
+                signature,
+            ),
+            (Err(_), _, _, _) => Err(ContractError::InvalidAddress(from)),
+            (_, Err(_), _, _) => Err(ContractError::InvalidAddress(to)),
+            (_, _, Err(err), _) => Err(ContractError::ParseError(err.to_string())),
+            (_, _, _, Err(err)) => Err(ContractError::ParseError(err.to_string())),
+        },
+        Action::Nonce { address } => match address.parse() {
+            Ok(address) => nonce(current_state, address),
+            Err(_) => Err(ContractError::InvalidAddress(address)),
+        },
+        Action::Escrow { id } => match id.parse() {
+            Ok(id) => escrow(current_state, id),
+            Err(err) => Err(ContractError::ParseError(err.to_string())),
+        },
+        Action::CreateEscrow {
+            to,
+            amount,
+            hashlock,
+            expiry_height,
+        } => match (to.parse(), amount.parse(), expiry_height.parse()) {
+            (Ok(to), Ok(amount), Ok(expiry_height)) => create_escrow(
+                current_state,
+                caller()?,
+                transaction_id()?,
+                Block::height() as u128,
+                to,
+                amount,
+                hashlock,
+                expiry_height,
+            ),
+            (Err(_), _, _) => Err(ContractError::InvalidAddress(to)),
+            (_, Err(err), _) => Err(ContractError::ParseError(err.to_string())),
+            (_, _, Err(err)) => Err(ContractError::ParseError(err.to_string())),
+        },
+        Action::Claim { id, preimage } => match id.parse() {
+            Ok(id) => claim(current_state, Block::height() as u128, id, preimage),
+            Err(err) => Err(ContractError::ParseError(err.to_string())),
+        },
+        Action::Refund { id } => match id.parse() {
+            Ok(id) => refund(current_state, Block::height() as u128, id),
+            Err(err) => Err(ContractError::ParseError(err.to_string())),
+        },
     }
 }
@@ -1,16 +1,38 @@
-use bundlr_contracts_shared::{Address, Amount};
+use bundlr_contracts_shared::{Address, Amount, BlockHeight, TransactionId};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
 pub enum ContractError {
     RuntimeError(String),
     ParseError(String),
     InvalidAddress(String),
     AmountMustBeHigherThanZero,
+    Forbidden,
+    InvalidName(String),
+    InvalidTicker(String),
+    InvalidDescription,
+    DecimalsLocked,
+    InvalidFee(u16),
     InvalidBalance(Amount),
     InvalidSpenderAllowance {
         owner: Address,
         spender: Address,
         amount: Amount,
     },
+    InvalidSpendingPeriod,
+    SpendingLimitExceeded(Amount),
+    SpendingLimitNotSet {
+        owner: Address,
+        spender: Address,
+    },
+    InvalidNonce(u64),
+    InvalidPublicKey,
+    InvalidSignature,
+    DeadlinePassed(BlockHeight),
+    InvalidHashlock(String),
+    InvalidExpiryHeight(BlockHeight),
+    InvalidPreimage,
+    EscrowNotFound(TransactionId),
+    EscrowExpired(TransactionId),
+    EscrowNotExpired(BlockHeight),
 }
@@ -4,3 +4,5 @@ mod contract;
 pub mod contract_utils;
 pub mod error;
 pub mod state;
+#[cfg(test)]
+mod testing;
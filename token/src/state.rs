@@ -1,15 +1,104 @@
-use bundlr_contracts_shared::{Address, Amount};
+use bundlr_contracts_shared::{u128_utils, Address, Amount, BlockHeight, TransactionId};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Escrow {
+    pub from: Address,
+    pub to: Address,
+    pub amount: Amount,
+    // hex encoded SHA-256 hash of the preimage required to claim the escrow
+    pub hashlock: String,
+    pub expiry_height: BlockHeight,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingLimit {
+    // maximum amount the spender can pull from the allowance within a period
+    pub amount: Amount,
+    // length of the period in blocks, periods are aligned to multiples of this value
+    #[serde(with = "u128_utils")]
+    pub period: u128,
+    pub period_start: BlockHeight,
+    pub spent: Amount,
+}
+
+impl SpendingLimit {
+    pub fn current_period_start(&self, current_block_height: u128) -> BlockHeight {
+        BlockHeight(current_block_height - current_block_height % self.period)
+    }
+
+    pub fn remaining(&self, current_block_height: u128) -> Amount {
+        if self.current_period_start(current_block_height) != self.period_start {
+            self.amount
+        } else if self.spent < self.amount {
+            self.amount - self.spent
+        } else {
+            Amount::ZERO
+        }
+    }
+}
+
+pub const BASIS_POINTS: u16 = 10000;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeConfig {
+    // fee taken from each transfer, in basis points of the transferred amount
+    pub fee_bps: u16,
+    pub treasury: Address,
+    // transfers from or to these addresses are not charged a fee
+    pub exempt: HashSet<Address>,
+}
+
+impl FeeConfig {
+    pub fn fee(&self, from: &Address, to: &Address, amount: Amount) -> Amount {
+        if self.exempt.contains(from) || self.exempt.contains(to) {
+            Amount::ZERO
+        } else {
+            // split the multiplication so that large amounts cannot overflow
+            Amount::from(
+                *amount / BASIS_POINTS as u128 * self.fee_bps as u128
+                    + *amount % BASIS_POINTS as u128 * self.fee_bps as u128 / BASIS_POINTS as u128,
+            )
+        }
+    }
+}
+
+// State written before the flag was introduced may already have had transfers,
+// so decimals are kept locked unless the state says otherwise
+fn default_decimals_locked() -> bool {
+    true
+}
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct State {
     pub ticker: String,
     pub name: Option<String>,
+    #[serde(default)]
+    pub logo: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
     pub decimals: u8,
+    // set on the first transfer, after which decimals can no longer be changed
+    #[serde(default = "default_decimals_locked")]
+    pub decimals_locked: bool,
     pub total_supply: Amount,
     pub owner: Address,
     pub balances: HashMap<Address, Amount>,
     pub allowances: HashMap<Address, HashMap<Address, Amount>>,
+    // spending limits matching the allowances, keyed the same way: owner -> spender
+    #[serde(default)]
+    pub spending_limits: HashMap<Address, HashMap<Address, SpendingLimit>>,
+    #[serde(default)]
+    pub fee_config: Option<FeeConfig>,
+    // next expected nonce per address for relayed transfers
+    #[serde(default)]
+    pub nonces: HashMap<Address, u64>,
+    // key: TransactionId, ID of the tx in which the escrow was created
+    #[serde(default)]
+    pub escrows: HashMap<TransactionId, Escrow>,
 }
@@ -0,0 +1,21 @@
+// Fixtures shared by the unit tests of the actions
+
+use bundlr_contracts_shared::{Address, Amount};
+
+use crate::{action::ActionResult, contract_utils::handler_result::HandlerResult, state::State};
+
+pub fn address(address: &str) -> Address {
+    Address::try_from(address).unwrap()
+}
+
+pub fn balance(state: &State, owner: &str) -> Amount {
+    *state.balances.get(&address(owner)).unwrap_or(&Amount::ZERO)
+}
+
+pub fn new_state(result: ActionResult) -> State {
+    match result {
+        Ok(HandlerResult::NewState(state)) => state,
+        Ok(HandlerResult::QueryResponse(_)) => panic!("expected a new state, got a query response"),
+        Err(err) => panic!("expected a new state, got {:?}", err),
+    }
+}
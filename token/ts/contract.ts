@@ -11,7 +11,10 @@ import {
 export type TokenState = {
   ticker: string;
   name: string | null | unknown;
+  logo: string | null;
+  description: string | null;
   decimals: number;
+  decimalsLocked: boolean;
   totalSupply: string;
   owner: string;
   balances: {
@@ -22,6 +25,65 @@ export type TokenState = {
       [key: string]: string;
     };
   };
+  spendingLimits: {
+    [key: string]: {
+      [key: string]: {
+        amount: string;
+        period: string;
+        periodStart: string;
+        spent: string;
+      };
+    };
+  };
+  feeConfig: FeeConfig | null;
+  nonces: {
+    [key: string]: number;
+  };
+  escrows: {
+    [key: string]: Escrow;
+  };
+};
+
+export type FeeConfig = {
+  feeBps: number;
+  treasury: string;
+  exempt: string[];
+};
+
+export type RelayedTransfer = {
+  from: string;
+  to: string;
+  amount: bigint;
+  nonce: number;
+  deadline: bigint;
+  publicKey?: string;
+  signature: string;
+};
+
+export type Escrow = {
+  from: string;
+  to: string;
+  amount: string;
+  hashlock: string;
+  expiryHeight: string;
+};
+
+export type TokenInfo = {
+  ticker: string;
+  name: string | null;
+  logo: string | null;
+  description: string | null;
+  decimals: number;
+  totalSupply: bigint;
+  owner: string;
+};
+
+export type MetadataUpdate = {
+  name?: string;
+  ticker?: string;
+  logo?: string;
+  description?: string;
+  decimals?: number;
 };
 
 export class Balance {
@@ -68,20 +130,69 @@ export class Allowance {
   }
 }
 
+export class SpendingLimit {
+  limit: bigint;
+  remaining: bigint;
+  periodEnds: bigint;
+  owner: string;
+  spender: string;
+
+  constructor({
+    limit,
+    remaining,
+    periodEnds,
+    owner,
+    spender,
+  }: {
+    limit: string;
+    remaining: string;
+    periodEnds: string;
+    owner: string;
+    spender: string;
+  }) {
+    this.limit = BigInt(limit);
+    this.remaining = BigInt(remaining);
+    this.periodEnds = BigInt(periodEnds);
+    this.owner = owner;
+    this.spender = spender;
+  }
+}
+
 export interface TokenContract extends Contract<TokenState> {
   allowance(owner: string, spender: string): Promise<Allowance>;
   balanceOf(target: string): Promise<Balance>;
   currentState(): Promise<TokenState>;
   decimals(): Promise<number>;
+  info(): Promise<TokenInfo>;
+  feeConfig(): Promise<FeeConfig | null>;
+  escrow(id: string): Promise<Escrow>;
+  nonce(address: string): Promise<number>;
+  spendingLimit(owner: string, spender: string): Promise<SpendingLimit>;
   name(): Promise<string | null | unknown>;
   symbol(): Promise<string>;
   totalSupply(): Promise<bigint>;
 
   approve(spender: string, value: bigint): Promise<string>;
+  updateMetadata(update: MetadataUpdate): Promise<string>;
+  setFeeConfig(config: FeeConfig): Promise<string>;
+  setSpendingLimit(
+    spender: string,
+    amount: bigint,
+    period: bigint
+  ): Promise<string>;
   burn(amount: bigint): Promise<string>;
   burnFrom(from: string, amount: bigint): Promise<string>;
   transfer(to: string, value: bigint): Promise<string>;
   transferFrom(from: string, to: string, value: bigint): Promise<string>;
+  createEscrow(
+    to: string,
+    amount: bigint,
+    hashlock: string,
+    expiryHeight: bigint
+  ): Promise<string>;
+  claim(id: string, preimage: string): Promise<string>;
+  relayedTransfer(transfer: RelayedTransfer): Promise<string>;
+  refund(id: string): Promise<string>;
 }
 
 class TokenContractImpl
@@ -140,6 +251,43 @@ class TokenContractImpl
     return BigInt(interactionResult.result as string);
   }
 
+  async info() {
+    const interactionResult = await this.viewState({
+      function: "info",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    const info = interactionResult.result as Omit<TokenInfo, "totalSupply"> & {
+      totalSupply: string;
+    };
+    return { ...info, totalSupply: BigInt(info.totalSupply) };
+  }
+
+  async feeConfig() {
+    const interactionResult = await this.viewState({
+      function: "feeConfig",
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as FeeConfig | null;
+  }
+
+  async setFeeConfig(config: FeeConfig) {
+    return this.write({
+      function: "setFeeConfig",
+      ...config,
+    });
+  }
+
+  async updateMetadata(update: MetadataUpdate) {
+    return this.write({
+      function: "updateMetadata",
+      ...update,
+    });
+  }
+
   async balanceOf(target: string): Promise<Balance> {
     const interactionResult = await this.viewState({
       function: "balanceOf",
@@ -216,6 +364,101 @@ class TokenContractImpl
     );
   }
 
+  async setSpendingLimit(spender: string, amount: bigint, period: bigint) {
+    return this.write({
+      function: "setSpendingLimit",
+      spender,
+      amount: amount.toString(),
+      period: period.toString(),
+    });
+  }
+
+  async spendingLimit(owner: string, spender: string) {
+    const interactionResult = await this.viewState({
+      function: "spendingLimit",
+      owner,
+      spender,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return new SpendingLimit(
+      interactionResult.result as {
+        limit: string;
+        remaining: string;
+        periodEnds: string;
+        owner: string;
+        spender: string;
+      }
+    );
+  }
+
+  async nonce(address: string) {
+    const interactionResult = await this.viewState({
+      function: "nonce",
+      address,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return (interactionResult.result as { nonce: number; address: string })
+      .nonce;
+  }
+
+  async relayedTransfer(transfer: RelayedTransfer) {
+    return this.write({
+      function: "relayedTransfer",
+      from: transfer.from,
+      to: transfer.to,
+      amount: transfer.amount.toString(),
+      nonce: transfer.nonce,
+      deadline: transfer.deadline.toString(),
+      publicKey: transfer.publicKey,
+      signature: transfer.signature,
+    });
+  }
+
+  async escrow(id: string) {
+    const interactionResult = await this.viewState({
+      function: "escrow",
+      id,
+    });
+    if (interactionResult.type !== "ok") {
+      throw Error(interactionResult.errorMessage);
+    }
+    return interactionResult.result as Escrow;
+  }
+
+  async createEscrow(
+    to: string,
+    amount: bigint,
+    hashlock: string,
+    expiryHeight: bigint
+  ) {
+    return this.write({
+      function: "createEscrow",
+      to,
+      amount: amount.toString(),
+      hashlock,
+      expiryHeight: expiryHeight.toString(),
+    });
+  }
+
+  async claim(id: string, preimage: string) {
+    return this.write({
+      function: "claim",
+      id,
+      preimage,
+    });
+  }
+
+  async refund(id: string) {
+    return this.write({
+      function: "refund",
+      id,
+    });
+  }
+
   write(input: any): Promise<string> {
     return this._mainnet
       ? this.bundleInteraction(input).then((response) => {
@@ -22,6 +22,9 @@ pub enum Action {
     BundlersContract,
     Join { stake: Amount, url: Url },
     Leave,
+    WithdrawStake,
+    IncreaseStake { amount: Amount },
+    DecreaseStake { amount: Amount },
     UpdateEpoch,
     ProposeSlash { proposal: Proposal },
     VoteSlash { tx: TransactionId, vote: Vote },
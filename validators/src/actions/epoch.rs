@@ -91,15 +91,20 @@ pub async fn update_epoch(mut state: State) -> ActionResult {
 
     let mut rng = Xoshiro256PlusPlus::from_seed(seed.0);
 
+    // Leaving validators are not nominated
+    let candidates = state
+        .validators
+        .iter()
+        .filter(|(_, validator)| validator.leaving.is_none())
+        .map(|(address, _)| address)
+        .collect::<Vec<&Address>>();
+
     // Pick 10 random nominees or pick all if number of validatros is 10 or less
-    if state.validators.len() <= state.max_num_nominated_validators as usize {
-        state.nominated_validators = state.validators.keys().cloned().collect::<Vec<Address>>();
+    if candidates.len() <= state.max_num_nominated_validators as usize {
+        state.nominated_validators = candidates.into_iter().cloned().collect::<Vec<Address>>();
     } else {
-        state.nominated_validators = pick_random_nominees(
-            &mut rng,
-            &state.validators.keys().collect::<Vec<&Address>>(),
-            state.max_num_nominated_validators,
-        );
+        state.nominated_validators =
+            pick_random_nominees(&mut rng, &candidates, state.max_num_nominated_validators);
     };
 
     // on each epoch update, check if there are any expired slash proposals
@@ -78,6 +78,8 @@ pub async fn join(mut state: State, stake: Amount, url: Url) -> ActionResult {
             address: caller,
             stake,
             url,
+            leaving: None,
+            unbonding: Vec::new(),
         },
     );
 
@@ -1,12 +1,5 @@
-use bundlr_contracts_shared::{Address, Amount};
-
-use serde::{Deserialize, Serialize};
-
-#[cfg(feature = "js-runtime")]
-use bundlr_contracts_shared::contract_utils::js_imports::SmartWeave;
-
 #[cfg(feature = "js-runtime")]
-use wasm_bindgen::JsValue;
+use bundlr_contracts_shared::{Address, Amount};
 
 #[cfg(feature = "js-runtime")]
 use crate::{
@@ -14,53 +7,235 @@ use crate::{
     state::State,
 };
 
-#[derive(Serialize)]
-struct Input {
-    function: String,
-    to: Address,
-    amount: Amount,
-}
-
-#[derive(Debug, Deserialize)]
-struct Result {
-    #[serde(rename = "type")]
-    result_type: String,
-}
-
 #[cfg(feature = "js-runtime")]
-pub async fn leave(mut state: State) -> ActionResult {
-    let caller = SmartWeave::caller()
-        .parse::<Address>()
-        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+use super::{slashing, token};
 
+// Leaving starts the unbonding, the stake is kept until state.unbonding_epochs
+// epochs have passed so that it can still be slashed for votes cast before leaving
+#[cfg(feature = "js-runtime")]
+pub async fn leave(mut state: State, caller: Address) -> ActionResult {
     if state.nominated_validators.contains(&caller) {
         return Err(ContractError::NominatedValidatorCannotLeave(caller));
     }
 
-    // FIXME: if the caller has voted in a currently open slash proposal, prevent leaving
+    // Votes of the caller have to be counted before it can leave
+    let open_votes = slashing::open_votes(&state, &caller);
+    if !open_votes.is_empty() {
+        return Err(ContractError::OpenVotes(open_votes));
+    }
+
+    let validator = if let Some(validator) = state.validators.get_mut(&caller) {
+        validator
+    } else {
+        return Err(ContractError::InvalidValidator(caller));
+    };
+
+    if validator.leaving.is_some() {
+        return Err(ContractError::AlreadyLeaving);
+    }
+
+    validator.leaving = Some(state.epoch.clone());
 
-    let validator = if let Some(validator) = state.validators.remove(&caller) {
+    Ok(HandlerResult::NewState(state))
+}
+
+// Withdraws the whole stake once the unbonding of a leaving validator is
+// over, otherwise the decreased stake which has finished unbonding
+#[cfg(feature = "js-runtime")]
+pub async fn withdraw_stake(mut state: State, caller: Address) -> ActionResult {
+    let unbonding_epochs = state.unbonding_epochs as u128;
+    let validator = if let Some(validator) = state.validators.get_mut(&caller) {
         validator
     } else {
         return Err(ContractError::InvalidValidator(caller));
     };
 
-    let result = SmartWeave::write(
-        &state.token.to_string(),
-        JsValue::from_serde(&Input {
-            function: "transfer".to_string(),
-            to: caller.clone(),
-            amount: validator.stake,
-        })
-        .unwrap(),
-    )
-    .await;
-
-    let result: Result = result.into_serde().unwrap();
-
-    if result.result_type != "ok" {
-        return Err(ContractError::TransferFailed);
+    if let Some(ref leaving) = validator.leaving {
+        let withdrawable_at = leaving.seq + unbonding_epochs;
+        if state.epoch.seq < withdrawable_at {
+            return Err(ContractError::WithdrawBlocked(withdrawable_at));
+        }
+
+        // We can safely unwrap here, the validator was checked above
+        let validator = state.validators.remove(&caller).unwrap();
+
+        token::transfer(&state.token, caller, validator.slashable_stake()).await?;
+
+        return Ok(HandlerResult::NewState(state));
+    }
+
+    // Decreases are made in increasing epochs, the first one matures first
+    let withdrawable_at = match validator.unbonding.first() {
+        Some((epoch, _)) => epoch.seq + unbonding_epochs,
+        None => return Err(ContractError::NothingToWithdraw),
+    };
+    if state.epoch.seq < withdrawable_at {
+        return Err(ContractError::WithdrawBlocked(withdrawable_at));
     }
 
+    let current_epoch = state.epoch.seq;
+    let (matured, unbonding) = validator
+        .unbonding
+        .drain(..)
+        .partition::<Vec<_>, _>(|(epoch, _)| epoch.seq + unbonding_epochs <= current_epoch);
+    validator.unbonding = unbonding;
+    let amount = matured
+        .into_iter()
+        .fold(Amount::ZERO, |total, (_, amount)| total + amount);
+
+    token::transfer(&state.token, caller, amount).await?;
+
     Ok(HandlerResult::NewState(state))
 }
+
+#[cfg(all(test, feature = "js-runtime"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use bundlr_contracts_shared::{Address, Amount};
+    use futures::executor::LocalPool;
+
+    use crate::{
+        action::ActionResult,
+        actions::{
+            stake::decrease_stake,
+            token::mock::{contract_address, take_transfers},
+        },
+        contract_utils::handler_result::HandlerResult,
+        epoch::Epoch,
+        error::ContractError,
+        state::{State, Validator},
+    };
+
+    use super::{leave, withdraw_stake};
+
+    fn address(address: &str) -> Address {
+        address.try_into().unwrap()
+    }
+
+    fn epoch(seq: u128) -> Epoch {
+        Epoch {
+            seq,
+            tx: address(&format!("epoch_{}", seq)),
+            height: seq * 500,
+        }
+    }
+
+    // Validator v1 with 300 staked, 200 above the minimum, two epochs of unbonding
+    fn state() -> State {
+        let validator = Validator {
+            address: address("v1"),
+            url: "https://v1.example.com".parse().unwrap(),
+            stake: Amount::from(300),
+            leaving: None,
+            unbonding: Vec::new(),
+        };
+
+        State {
+            epoch: epoch(5),
+            minimum_stake: Amount::from(100),
+            token: address("token"),
+            unbonding_epochs: 2,
+            validators: HashMap::from([(address("v1"), validator)]),
+            ..Default::default()
+        }
+    }
+
+    fn new_state(result: ActionResult) -> State {
+        match result {
+            Ok(HandlerResult::NewState(state)) => state,
+            _ => panic!("expected a new state"),
+        }
+    }
+
+    #[test]
+    fn decreased_stake_unbonds_before_it_is_withdrawn() {
+        let mut pool = LocalPool::new();
+
+        let state =
+            new_state(pool.run_until(decrease_stake(state(), address("v1"), Amount::from(50))));
+        let state =
+            new_state(pool.run_until(decrease_stake(state, address("v1"), Amount::from(50))));
+        assert!(take_transfers().is_empty());
+
+        // Decreased stake no longer counts for votes but stays slashable
+        let validator = &state.validators[&address("v1")];
+        assert_eq!(validator.stake, Amount::from(200));
+        assert_eq!(validator.unbonding, vec![(epoch(5), Amount::from(100))]);
+        assert_eq!(validator.slashable_stake(), Amount::from(300));
+
+        let mut state = state;
+        state.epoch = epoch(6);
+        let state =
+            new_state(pool.run_until(decrease_stake(state, address("v1"), Amount::from(20))));
+
+        let result = pool.run_until(withdraw_stake(state.clone(), address("v1")));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked(7)));
+
+        let mut state = state;
+        state.epoch = epoch(7);
+        let state = new_state(pool.run_until(withdraw_stake(state, address("v1"))));
+        let validator = &state.validators[&address("v1")];
+        assert_eq!(validator.unbonding, vec![(epoch(6), Amount::from(20))]);
+        assert_eq!(validator.slashable_stake(), Amount::from(200));
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                contract_address(),
+                address("v1"),
+                Amount::from(100)
+            )]
+        );
+    }
+
+    #[test]
+    fn nothing_to_withdraw_without_decrease_or_leave() {
+        let mut pool = LocalPool::new();
+
+        let result = pool.run_until(withdraw_stake(state(), address("v1")));
+        assert_eq!(result.err(), Some(ContractError::NothingToWithdraw));
+
+        let result = pool.run_until(withdraw_stake(state(), address("v2")));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::InvalidValidator(address("v2")))
+        );
+    }
+
+    #[test]
+    fn leaving_validator_withdraws_unbonding_stake_with_the_rest() {
+        let mut pool = LocalPool::new();
+
+        let state =
+            new_state(pool.run_until(decrease_stake(state(), address("v1"), Amount::from(100))));
+        let mut state = new_state(pool.run_until(leave(state, address("v1"))));
+
+        let result = pool.run_until(decrease_stake(
+            state.clone(),
+            address("v1"),
+            Amount::from(50),
+        ));
+        assert_eq!(
+            result.err(),
+            Some(ContractError::ValidatorLeaving(address("v1")))
+        );
+
+        state.epoch = epoch(6);
+        let result = pool.run_until(withdraw_stake(state.clone(), address("v1")));
+        assert_eq!(result.err(), Some(ContractError::WithdrawBlocked(7)));
+
+        state.epoch = epoch(7);
+        let state = new_state(pool.run_until(withdraw_stake(state, address("v1"))));
+        assert!(state.validators.is_empty());
+        assert_eq!(
+            take_transfers(),
+            vec![(
+                address("token"),
+                contract_address(),
+                address("v1"),
+                Amount::from(300)
+            )]
+        );
+    }
+}
@@ -1,6 +1,10 @@
 mod epoch;
 mod join;
 mod leave;
+#[cfg(feature = "js-runtime")]
+mod stake;
+#[cfg(feature = "js-runtime")]
+mod token;
 
 pub mod queries;
 pub mod slashing;
@@ -10,4 +14,6 @@ pub use epoch::update_epoch;
 #[cfg(feature = "js-runtime")]
 pub use join::join;
 #[cfg(feature = "js-runtime")]
-pub use leave::leave;
+pub use leave::{leave, withdraw_stake};
+#[cfg(feature = "js-runtime")]
+pub use stake::{decrease_stake, increase_stake};
@@ -78,8 +78,14 @@ pub async fn propose(
     current_block_height: u128,
     proposal: Proposal,
 ) -> ActionResult {
-    if !state.validators.contains_key(&caller) {
-        return Err(ContractError::InvalidValidator(caller));
+    match state.validators.get(&caller) {
+        Some(validator) if validator.leaving.is_some() => {
+            return Err(ContractError::ValidatorLeaving(caller));
+        }
+        Some(_) => {}
+        None => {
+            return Err(ContractError::InvalidValidator(caller));
+        }
     }
 
     let tx_id = TransactionId::from_str(&proposal.id).map_err(|_| {
@@ -109,6 +115,11 @@ pub async fn propose(
         return Err(ContractError::TooManyProposals);
     }
 
+    // votes are weighted with the stakes at the time of the proposal, so that
+    // stake changes during the voting cannot swing it
+    let stakes = voting_validators(&state.validators);
+    state.stake_snapshots.insert(tx_id.clone(), stakes);
+
     let voting_data = {
         let mut votes = HashMap::new();
         votes.insert(caller.clone(), Vote::For);
@@ -136,8 +147,19 @@ pub async fn vote(
     tx: TransactionId,
     vote: Vote,
 ) -> ActionResult {
-    if !state.validators.contains_key(&caller) {
-        return Err(ContractError::InvalidValidator(caller));
+    match state.validators.get(&caller) {
+        Some(validator) if validator.leaving.is_some() => {
+            return Err(ContractError::ValidatorLeaving(caller));
+        }
+        Some(_) => {}
+        None => {
+            return Err(ContractError::InvalidValidator(caller));
+        }
+    }
+
+    let stakes = voting_stakes(&state.stake_snapshots, &state.validators, &tx);
+    if !stakes.contains_key(&caller) {
+        return Err(ContractError::JoinedAfterProposal);
     }
 
     let (_, _, height, _, ref mut voting_data) =
@@ -165,32 +187,24 @@ pub async fn vote(
         return Err(ContractError::AlreadyVoted);
     }
 
-    let (total_stake, voted_stake, result) = evaluate_votes(&state.validators, votes);
+    let (total_stake, voted_stake, result) = evaluate_votes(&stakes, votes);
 
     // close voting, if all votes are casted or the remaining stake cannot flip the vote
     if total_stake - voted_stake < result.abs() as u128 || total_stake == voted_stake {
         let result = result.into();
         let votes = votes
             .iter()
-            .map(|(address, vote)| {
-                (
-                    address.clone(),
-                    (
-                        *vote,
-                        state
-                            .validators
-                            .get(address)
-                            .map(|validator| validator.stake)
-                            .unwrap()
-                            .clone(),
-                    ),
-                )
+            .filter_map(|(address, vote)| {
+                stakes
+                    .get(address)
+                    .map(|stake| (address.clone(), (*vote, *stake)))
             })
             .collect();
         *voting_data = Voting::Closed {
             votes,
             final_vote: result,
         };
+        state.stake_snapshots.remove(&tx);
 
         match result {
             Vote::For => on_positive_voting_result(&state, &tx),
@@ -201,25 +215,47 @@ pub async fn vote(
     Ok(HandlerResult::NewState(state))
 }
 
-fn evaluate_votes(
+// Stakes the votes on the proposal are weighted with. Proposals made before the
+// snapshots were introduced fall back to the current stakes.
+fn voting_stakes(
+    snapshots: &HashMap<TransactionId, HashMap<Address, Stake>>,
     validators: &HashMap<Address, Validator>,
+    tx: &TransactionId,
+) -> HashMap<Address, Stake> {
+    snapshots
+        .get(tx)
+        .cloned()
+        .unwrap_or_else(|| voting_validators(validators))
+}
+
+// Stakes of the validators allowed to vote, leaving validators are not
+fn voting_validators(validators: &HashMap<Address, Validator>) -> HashMap<Address, Stake> {
+    validators
+        .iter()
+        .filter(|(_, validator)| validator.leaving.is_none())
+        .map(|(address, validator)| (address.clone(), validator.stake))
+        .collect()
+}
+
+fn evaluate_votes(
+    stakes: &HashMap<Address, Stake>,
     votes: &HashMap<Address, Vote>,
 ) -> (u128, u128, i128) {
     // dereference Amount to u128 so that the computaion later is easier
-    let total_stake = *validators
-        .iter()
-        .fold(Amount::default(), |total_stake, (_, validator)| {
-            total_stake + validator.stake
-        });
+    let total_stake = *stakes
+        .values()
+        .fold(Amount::default(), |total_stake, stake| total_stake + *stake);
 
     let (voted_stake, result) =
         votes
             .iter()
             .fold((0u128, 0i128), |(voted_stake, result), (address, vote)| {
-                let voter_stake = validators
-                    .get(address)
-                    .map(|validator| validator.stake)
-                    .expect("Could not find the validator who has voted earlier");
+                // votes of validators who have left since are not counted, which
+                // can only happen for proposals made before the stake snapshots
+                let voter_stake = match stakes.get(address) {
+                    Some(stake) => *stake,
+                    None => return (voted_stake, result),
+                };
                 let stake_weighted_vote = match vote {
                     Vote::For => {
                         i128::try_from(*voter_stake).expect("Could not fit validator stake in i128")
@@ -232,6 +268,22 @@ fn evaluate_votes(
     (total_stake, voted_stake, result)
 }
 
+// Open proposals the validator has made or voted in, sorted by the ID of the
+// tx the proposal is referring to
+pub(super) fn open_votes(state: &State, validator: &Address) -> Vec<TransactionId> {
+    let mut open_votes = state
+        .slash_proposals
+        .iter()
+        .filter(|(_, (_, proposer, _, _, voting_data))| match voting_data {
+            Voting::Open(votes) => proposer == validator || votes.contains_key(validator),
+            Voting::Closed { .. } => false,
+        })
+        .map(|(tx, _)| tx.clone())
+        .collect::<Vec<TransactionId>>();
+    open_votes.sort();
+    open_votes
+}
+
 fn on_positive_voting_result(_state: &State, _tx: &TransactionId) {
     // validators voted for bundler to be slashed
 
@@ -260,8 +312,7 @@ pub(super) fn on_update_epoch(state: &mut State, current_block_height: u128) {
             current_block_height > height + state.slash_proposal_lifetime as u128
                 && voting_data.is_open()
         })
-        .map(|(_, (_, _, _, _, voting_data))| voting_data)
-        .for_each(|voting_data| {
+        .for_each(|(tx, (_, _, _, _, voting_data))| {
             let votes = match voting_data {
                 Voting::Open(votes) => votes,
                 Voting::Closed {
@@ -270,7 +321,8 @@ pub(super) fn on_update_epoch(state: &mut State, current_block_height: u128) {
                 } => unreachable!(), // We already checked above that this is open
             };
 
-            let (total_stake, voted_stake, result) = evaluate_votes(&state.validators, &votes);
+            let stakes = voting_stakes(&state.stake_snapshots, &state.validators, tx);
+            let (total_stake, voted_stake, result) = evaluate_votes(&stakes, votes);
 
             let final_vote = if voted_stake as f64 > 0.75 * total_stake as f64 {
                 Vote::from(result)
@@ -279,23 +331,22 @@ pub(super) fn on_update_epoch(state: &mut State, current_block_height: u128) {
             };
             let votes = votes
                 .iter()
-                .map(|(validator, vote)| {
-                    (
-                        validator.clone(),
-                        (
-                            *vote,
-                            state
-                                .validators
-                                .get(validator)
-                                .map(|validator| validator.stake)
-                                .unwrap()
-                                .clone(),
-                        ),
-                    )
+                .filter_map(|(validator, vote)| {
+                    stakes
+                        .get(validator)
+                        .map(|stake| (validator.clone(), (*vote, *stake)))
                 })
                 .collect();
             *voting_data = Voting::Closed { votes, final_vote }
         });
+
+    // snapshots are only needed while the voting is open
+    let slash_proposals = &state.slash_proposals;
+    state.stake_snapshots.retain(|tx, _| {
+        slash_proposals
+            .get(tx)
+            .is_some_and(|(_, _, _, _, voting_data)| voting_data.is_open())
+    });
 }
 
 #[cfg(test)]
@@ -313,7 +364,17 @@ mod tests {
         state::{State, Validator},
     };
 
-    use super::{evaluate_votes, on_update_epoch, vote, TransactionId, Vote};
+    use super::{
+        evaluate_votes, on_update_epoch, open_votes, vote, voting_stakes, TransactionId, Vote,
+    };
+
+    fn stakes(state: &State) -> HashMap<Address, Amount> {
+        voting_stakes(
+            &state.stake_snapshots,
+            &state.validators,
+            &"tx2".try_into().unwrap(),
+        )
+    }
 
     fn state() -> State {
         static VALIDATORS_AND_STAKES: [(&str, u128, &str); 13] = [
@@ -342,6 +403,8 @@ mod tests {
                         address,
                         url: url.parse().unwrap(),
                         stake: stake.into(),
+                        leaving: None,
+                        unbonding: Vec::new(),
                     },
                 )
             })
@@ -480,7 +543,9 @@ mod tests {
             validators,
             nominated_validators,
             slash_proposal_lifetime: 300,
+            unbonding_epochs: 2,
             slash_proposals,
+            stake_snapshots: HashMap::new(),
         }
     }
 
@@ -498,7 +563,7 @@ mod tests {
             _ => unreachable!(),
         };
 
-        let (total_stake, _, _) = evaluate_votes(&state.validators, votes);
+        let (total_stake, _, _) = evaluate_votes(&stakes(&state), votes);
 
         assert_eq!(total_stake, 170020);
     }
@@ -517,7 +582,7 @@ mod tests {
             _ => unreachable!(),
         };
 
-        let (_, voted_stake, _) = evaluate_votes(&state.validators, votes);
+        let (_, voted_stake, _) = evaluate_votes(&stakes(&state), votes);
 
         assert_eq!(voted_stake, 105010);
     }
@@ -536,7 +601,7 @@ mod tests {
             _ => unreachable!(),
         };
 
-        let (_, _, result) = evaluate_votes(&state.validators, votes);
+        let (_, _, result) = evaluate_votes(&stakes(&state), votes);
 
         assert_eq!(result, 65010);
     }
@@ -717,4 +782,201 @@ mod tests {
 
         assert_eq!(result, Err(ContractError::InvalidValidator(caller)));
     }
+
+    #[test]
+    fn proposing_takes_a_snapshot_of_the_stakes() {
+        let mut pool = LocalPool::new();
+
+        let state = state();
+
+        if let Ok(HandlerResult::NewState(state)) = pool.run_until(propose(
+            state,
+            "a13".try_into().unwrap(),
+            "tx100".try_into().unwrap(),
+            2600,
+            Proposal {
+                id: "tx5".to_string(),
+                size: 100,
+                fee: 100,
+                currency: "BTC".to_string(),
+                block: 2550,
+                validator: "a13".to_string(),
+                signature: "foo".to_string(),
+            },
+        )) {
+            let snapshot = state
+                .stake_snapshots
+                .get("tx5".try_into().as_ref().unwrap())
+                .unwrap();
+
+            assert_eq!(snapshot.len(), 13);
+            assert_eq!(
+                snapshot.get(&"a12".try_into().unwrap()),
+                Some(&Amount::from(30000))
+            );
+        } else {
+            unreachable!("Proposal should have succeeded")
+        }
+    }
+
+    #[test]
+    fn votes_are_weighted_with_the_stake_snapshot() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        let snapshot = stakes(&state);
+        state
+            .stake_snapshots
+            .insert("tx2".try_into().unwrap(), snapshot);
+
+        // a12 decreases its stake after the proposal was made
+        state
+            .validators
+            .get_mut(&"a12".try_into().unwrap())
+            .unwrap()
+            .stake = Amount::from(100);
+
+        if let Ok(HandlerResult::NewState(state)) = pool.run_until(vote(
+            state,
+            "a12".try_into().unwrap(),
+            2400,
+            "tx2".try_into().unwrap(),
+            Vote::For,
+        )) {
+            match state
+                .slash_proposals
+                .get("tx2".try_into().as_ref().unwrap())
+                .unwrap()
+                .4
+            {
+                Voting::Open(_) => unreachable!("Voting should have been closed"),
+                Voting::Closed { ref votes, .. } => {
+                    assert_eq!(
+                        votes.get(&"a12".try_into().unwrap()),
+                        Some(&(Vote::For, Amount::from(30000)))
+                    );
+                }
+            }
+            assert!(!state
+                .stake_snapshots
+                .contains_key("tx2".try_into().as_ref().unwrap()));
+        } else {
+            unreachable!("Vote should have succeeded")
+        }
+    }
+
+    #[test]
+    fn validator_joined_after_the_proposal_cannot_vote() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        let mut snapshot = stakes(&state);
+        snapshot.remove(&"a12".try_into().unwrap());
+        state
+            .stake_snapshots
+            .insert("tx2".try_into().unwrap(), snapshot);
+
+        let result = pool.run_until(vote(
+            state,
+            "a12".try_into().unwrap(),
+            2400,
+            "tx2".try_into().unwrap(),
+            Vote::For,
+        ));
+
+        assert_eq!(result, Err(ContractError::JoinedAfterProposal));
+    }
+
+    #[test]
+    fn leaving_validator_cannot_vote() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        let caller: Address = "a12".try_into().unwrap();
+        state.validators.get_mut(&caller).unwrap().leaving = Some(state.epoch.clone());
+
+        let result = pool.run_until(vote(
+            state,
+            caller.clone(),
+            2400,
+            "tx2".try_into().unwrap(),
+            Vote::For,
+        ));
+
+        assert_eq!(result, Err(ContractError::ValidatorLeaving(caller)));
+    }
+
+    #[test]
+    fn leaving_validators_are_left_out_of_the_stake_snapshot() {
+        let mut pool = LocalPool::new();
+
+        let mut state = state();
+        let leaving: Address = "a12".try_into().unwrap();
+        state.validators.get_mut(&leaving).unwrap().leaving = Some(state.epoch.clone());
+
+        if let Ok(HandlerResult::NewState(state)) = pool.run_until(propose(
+            state,
+            "a13".try_into().unwrap(),
+            "tx100".try_into().unwrap(),
+            2600,
+            Proposal {
+                id: "tx5".to_string(),
+                size: 100,
+                fee: 100,
+                currency: "BTC".to_string(),
+                block: 2550,
+                validator: "a13".to_string(),
+                signature: "foo".to_string(),
+            },
+        )) {
+            let snapshot = state
+                .stake_snapshots
+                .get("tx5".try_into().as_ref().unwrap())
+                .unwrap();
+
+            assert_eq!(snapshot.len(), 12);
+            assert!(!snapshot.contains_key(&leaving));
+        } else {
+            unreachable!("Proposal should have succeeded")
+        }
+    }
+
+    #[test]
+    fn evaluate_votes_skips_validators_who_have_left() {
+        let mut state = state();
+
+        let votes = match state
+            .slash_proposals
+            .get("tx2".try_into().as_ref().unwrap())
+            .unwrap()
+            .4
+        {
+            Voting::Open(ref votes) => votes.clone(),
+            _ => unreachable!(),
+        };
+
+        // a2 voted against with 20000 and has withdrawn since
+        state.validators.remove(&"a2".try_into().unwrap());
+
+        let (total_stake, voted_stake, result) = evaluate_votes(&stakes(&state), &votes);
+
+        assert_eq!(total_stake, 150020);
+        assert_eq!(voted_stake, 85010);
+        assert_eq!(result, 85010);
+    }
+
+    #[test]
+    fn open_votes_lists_open_proposals_the_validator_has_made_or_voted_in() {
+        let state = state();
+
+        assert_eq!(
+            open_votes(&state, &"a3".try_into().unwrap()),
+            vec!["tx2".try_into().unwrap(), "tx3".try_into().unwrap()]
+        );
+        assert_eq!(
+            open_votes(&state, &"a12".try_into().unwrap()),
+            vec!["tx3".try_into().unwrap()]
+        );
+        assert!(open_votes(&state, &"a13".try_into().unwrap()).is_empty());
+    }
 }
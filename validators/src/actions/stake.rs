@@ -0,0 +1,65 @@
+use bundlr_contracts_shared::{Address, Amount};
+
+use crate::{
+    action::ActionResult, contract_utils::handler_result::HandlerResult, error::ContractError,
+    state::State,
+};
+
+use super::token;
+
+// Stake changes do not affect open votes, those are weighted with the stakes
+// snapshotted when the proposal was made
+pub async fn increase_stake(mut state: State, caller: Address, amount: Amount) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::InvalidStake);
+    }
+
+    match state.validators.get(&caller) {
+        Some(validator) if validator.leaving.is_some() => {
+            return Err(ContractError::ValidatorLeaving(caller));
+        }
+        Some(_) => {}
+        None => {
+            return Err(ContractError::InvalidValidator(caller));
+        }
+    }
+
+    token::transfer_to_contract(&state.token, caller.clone(), amount).await?;
+
+    // We can safely unwrap here, the validator was checked above
+    state.validators.get_mut(&caller).unwrap().stake += amount;
+
+    Ok(HandlerResult::NewState(state))
+}
+
+// Decreased stake unbonds for state.unbonding_epochs epochs before it can be
+// withdrawn, so that it can still be slashed for votes cast before the decrease
+pub async fn decrease_stake(mut state: State, caller: Address, amount: Amount) -> ActionResult {
+    if amount == Amount::ZERO {
+        return Err(ContractError::InvalidStake);
+    }
+
+    let validator = if let Some(validator) = state.validators.get_mut(&caller) {
+        validator
+    } else {
+        return Err(ContractError::InvalidValidator(caller));
+    };
+
+    if validator.leaving.is_some() {
+        return Err(ContractError::ValidatorLeaving(caller));
+    }
+
+    // Leaving is the way to get the minimum stake out
+    if validator.stake < amount || validator.stake - amount < state.minimum_stake {
+        return Err(ContractError::InvalidStake);
+    }
+
+    validator.stake -= amount;
+    // Decreases within the same epoch unbond together
+    match validator.unbonding.last_mut() {
+        Some((epoch, unbonding)) if *epoch == state.epoch => *unbonding += amount,
+        _ => validator.unbonding.push((state.epoch.clone(), amount)),
+    }
+
+    Ok(HandlerResult::NewState(state))
+}
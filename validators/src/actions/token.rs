@@ -0,0 +1,128 @@
+#[cfg(not(test))]
+use std::str::FromStr;
+
+#[cfg(not(test))]
+use bundlr_contracts_shared::contract_utils::js_imports::{Contract, SmartWeave};
+use bundlr_contracts_shared::{Address, Amount};
+#[cfg(not(test))]
+use serde::Deserialize;
+use serde::Serialize;
+#[cfg(not(test))]
+use wasm_bindgen::JsValue;
+
+use crate::error::ContractError;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "function", rename_all = "camelCase")]
+enum Input {
+    Transfer {
+        to: Address,
+        amount: Amount,
+    },
+    TransferFrom {
+        from: Address,
+        to: Address,
+        amount: Amount,
+    },
+}
+
+#[cfg(not(test))]
+#[derive(Debug, Deserialize)]
+struct Result {
+    #[serde(rename = "type")]
+    result_type: String,
+}
+
+#[cfg(not(test))]
+async fn write(token: &Address, input: Input) -> std::result::Result<(), ContractError> {
+    let result = SmartWeave::write(token, JsValue::from_serde(&input).unwrap()).await;
+
+    let result: Result = result
+        .into_serde()
+        .map_err(|err| ContractError::ParseError(err.to_string()))?;
+
+    if result.result_type != "ok" {
+        return Err(ContractError::TransferFailed);
+    }
+
+    Ok(())
+}
+
+// There is no token contract to call outside of the SmartWeave runtime, tests
+// get the transfers recorded instead
+#[cfg(test)]
+async fn write(token: &Address, input: Input) -> std::result::Result<(), ContractError> {
+    if token.as_ref() == mock::FAILING_TOKEN {
+        return Err(ContractError::TransferFailed);
+    }
+
+    mock::TRANSFERS.with(|transfers| transfers.borrow_mut().push((token.clone(), input)));
+
+    Ok(())
+}
+
+#[cfg(not(test))]
+fn contract_id() -> std::result::Result<Address, ContractError> {
+    Address::from_str(&Contract::id()).map_err(|err| ContractError::ParseError(err.to_string()))
+}
+
+#[cfg(test)]
+fn contract_id() -> std::result::Result<Address, ContractError> {
+    Ok(mock::contract_address())
+}
+
+/// Transfers tokens held by this contract to the given address.
+pub async fn transfer(
+    token: &Address,
+    to: Address,
+    amount: Amount,
+) -> std::result::Result<(), ContractError> {
+    write(token, Input::Transfer { to, amount }).await
+}
+
+/// Pulls tokens to this contract using the allowance `from` has given to it.
+pub async fn transfer_to_contract(
+    token: &Address,
+    from: Address,
+    amount: Amount,
+) -> std::result::Result<(), ContractError> {
+    let to = contract_id()?;
+
+    write(token, Input::TransferFrom { from, to, amount }).await
+}
+
+#[cfg(test)]
+pub mod mock {
+    use std::cell::RefCell;
+
+    use bundlr_contracts_shared::{Address, Amount};
+
+    use super::Input;
+
+    pub const CONTRACT_ID: &str = "validators_contract";
+    // transfers of this token fail
+    pub const FAILING_TOKEN: &str = "failing_token";
+
+    thread_local! {
+        pub(super) static TRANSFERS: RefCell<Vec<(Address, Input)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub fn contract_address() -> Address {
+        Address::try_from(CONTRACT_ID).unwrap()
+    }
+
+    /// Token, sender, recipient and amount of each transfer since the previous
+    /// call, the sender is the contract itself for plain transfers.
+    pub fn take_transfers() -> Vec<(Address, Address, Address, Amount)> {
+        TRANSFERS.with(|transfers| {
+            transfers
+                .borrow_mut()
+                .drain(..)
+                .map(|(token, input)| match input {
+                    Input::Transfer { to, amount } => (token, contract_address(), to, amount),
+                    Input::TransferFrom { from, to, amount } => (token, from, to, amount),
+                })
+                .collect()
+        })
+    }
+}
@@ -27,7 +27,14 @@ pub async fn handle(current_state: State, action: Action) -> ActionResult {
         Action::Epoch => actions::queries::epoch(current_state),
         Action::EpochDuration => actions::queries::epoch_duration(current_state),
         Action::Join { stake, url } => actions::join(current_state, stake, url).await,
-        Action::Leave => actions::leave(current_state).await,
+        Action::Leave => actions::leave(current_state, caller).await,
+        Action::WithdrawStake => actions::withdraw_stake(current_state, caller).await,
+        Action::IncreaseStake { amount } => {
+            actions::increase_stake(current_state, caller, amount).await
+        }
+        Action::DecreaseStake { amount } => {
+            actions::decrease_stake(current_state, caller, amount).await
+        }
         Action::UpdateEpoch => actions::update_epoch(current_state).await,
         Action::ProposeSlash { proposal } => {
             actions::slashing::propose(current_state, caller, tx_id, block_height, proposal).await
@@ -17,4 +17,10 @@ pub enum ContractError {
     ProposalExpired,
     VotingClosed,
     AlreadyVoted,
+    JoinedAfterProposal,
+    AlreadyLeaving,
+    OpenVotes(Vec<TransactionId>),
+    NothingToWithdraw,
+    ValidatorLeaving(Address),
+    WithdrawBlocked(u128),
 }
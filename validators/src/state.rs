@@ -15,6 +15,24 @@ pub struct Validator {
     pub address: Address,
     pub url: Url,
     pub stake: Amount,
+    // epoch in which the validator left, the stake stays slashable and the
+    // validator cannot vote until it is withdrawn
+    #[serde(default)]
+    pub leaving: Option<Epoch>,
+    // epochs in which the stake was decreased together with the decreased
+    // amount, unbonding the same way as the stake of a leaving validator
+    #[serde(default)]
+    pub unbonding: Vec<(Epoch, Amount)>,
+}
+
+impl Validator {
+    // Everything the contract holds for the validator, including the
+    // decreased stake which stays slashable until it is withdrawn
+    pub fn slashable_stake(&self) -> Amount {
+        self.unbonding
+            .iter()
+            .fold(self.stake, |total, (_, amount)| total + *amount)
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -30,6 +48,10 @@ pub struct State {
     pub validators: HashMap<Address, Validator>,
     pub nominated_validators: Vec<Address>,
     pub slash_proposal_lifetime: u16,
+    // number of epochs a leaving validator has to wait before withdrawing the
+    // stake, decreased stake unbonds for the same number of epochs
+    #[serde(default)]
+    pub unbonding_epochs: u16,
 
     // key: TransactionId, ID of the tx that proposal is referring to
     // value.0: Proposal, the actual proposal data (matching with validator's sign request data)
@@ -38,4 +60,9 @@ pub struct State {
     // value.3: TransactionId, tx ID in which the proposal was made
     // value.4: Voting, voting data for this proposal
     pub slash_proposals: HashMap<TransactionId, (Proposal, Address, u128, TransactionId, Voting)>,
+
+    // key: TransactionId, ID of the tx that an open proposal is referring to
+    // value: stakes of the validators when the proposal was made, used to weight the votes
+    #[serde(default)]
+    pub stake_snapshots: HashMap<TransactionId, HashMap<Address, Amount>>,
 }
@@ -237,12 +237,22 @@ describe("Bundlers Contract", () => {
     // TODO: check token balances
   });
 
-  it("leave removes validator and returns the stake", async () => {
+  it("leave keeps the stake until it is withdrawn", async () => {
     await connections[2].validators.leave();
     await mineBlock(arweave);
 
-    expect(await connections[2].validators.validators()).not.toEqual(
-      expect.objectContaining({ [accounts[2].address]: false })
+    expect(await connections[2].validators.validators()).toContain(
+      accounts[2].address
+    );
+  });
+
+  it("withdraw stake removes validator and returns the stake", async () => {
+    // unbonding period in the test state is zero epochs
+    await connections[2].validators.withdrawStake();
+    await mineBlock(arweave);
+
+    expect(await connections[2].validators.validators()).not.toContain(
+      accounts[2].address
     );
 
     // TODO: check token balances
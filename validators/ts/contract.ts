@@ -21,6 +21,7 @@ export class State {
   slashProposals: {
     [key: string]: [SlashProposal, string, string, string, any]; // TODO: model voting data correctly
   };
+  stakeSnapshots: { [key: string]: { [key: string]: string } };
 
   constructor(data: {
     bundler: string;
@@ -34,6 +35,7 @@ export class State {
     slashProposals: {
       [key: string]: [SlashProposal, string, string, string, any]; // TODO: model voting data correctly
     };
+    stakeSnapshots?: { [key: string]: { [key: string]: string } };
   }) {
     if (!data.bundler) {
       throw Error("Invalid data, 'bundler' not defined");
@@ -79,6 +81,8 @@ export class State {
       throw Error("Invalid data, 'slashProposals' not defined");
     }
     this.slashProposals = data.slashProposals;
+
+    this.stakeSnapshots = data.stakeSnapshots ?? {};
   }
 }
 
@@ -152,6 +156,9 @@ export interface ValidatorsContract extends Contract<State> {
   updateEpoch(): Promise<string>;
   join(stake: bigint, url: URL): Promise<string>;
   leave(): Promise<string>;
+  withdrawStake(): Promise<string>;
+  increaseStake(amount: bigint): Promise<string>;
+  decreaseStake(amount: bigint): Promise<string>;
   proposeSlash(proposal: SlashProposal): Promise<string>;
   voteSlash(tx: string, vote: "for" | "against"): Promise<string>;
 }
@@ -278,6 +285,26 @@ class ValidatorsContractImpl
     });
   }
 
+  async withdrawStake() {
+    return this.write({
+      function: "withdrawStake",
+    });
+  }
+
+  async increaseStake(amount: bigint) {
+    return this.write({
+      function: "increaseStake",
+      amount: amount.toString(),
+    });
+  }
+
+  async decreaseStake(amount: bigint) {
+    return this.write({
+      function: "decreaseStake",
+      amount: amount.toString(),
+    });
+  }
+
   async proposeSlash(proposal: SlashProposal) {
     return this.write({
       function: "proposeSlash",